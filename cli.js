@@ -315,6 +315,7 @@ function startUI() {
   const flags = {
     port: 3333,
     dir: null, // Will default to active project or home
+    socket: null, // Desktop app: serve HTTP on this socket instead of the port
     foreground: false  // Default to daemon mode
   };
 
@@ -338,6 +339,10 @@ function startUI() {
       flags.dir = args[++i] || null;
     } else if (arg.startsWith('--dir=')) {
       flags.dir = arg.split('=')[1] || null;
+    } else if (arg === '--socket') {
+      flags.socket = args[++i] || null;
+    } else if (arg.startsWith('--socket=')) {
+      flags.socket = arg.split('=')[1] || null;
     } else if (arg === '--foreground' || arg === '-f' || arg === '--daemon' || arg === '-D') {
       // --foreground runs in foreground, --daemon kept for backwards compat (now default)
       flags.foreground = (arg === '--foreground' || arg === '-f');
@@ -421,7 +426,7 @@ function startUI() {
 
   try {
     const manager = new ClaudeConfigManager();
-    const server = new ConfigUIServer(flags.port, flags.dir, manager);
+    const server = new ConfigUIServer(flags.port, flags.dir, manager, { socket: flags.socket });
     server.start();
   } catch (err) {
     console.error('Error: Failed to start server');
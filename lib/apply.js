@@ -313,6 +313,18 @@ function detectInstalledTools() {
     method: 'directory'
   };
 
+  if (TOOL_PATHS.codex) {
+    try {
+      execSync('which codex', { stdio: 'ignore' });
+      results.codex = { installed: true, method: 'command' };
+    } catch {
+      results.codex = {
+        installed: fs.existsSync(path.join(homeDir, '.codex')),
+        method: 'directory'
+      };
+    }
+  }
+
   return results;
 }
 
@@ -329,6 +341,10 @@ function applyForTools(registryPath, projectDir = null, tools = ['claude']) {
       results.gemini = applyForGemini(registryPath, projectDir);
     } else if (tool === 'antigravity') {
       results.antigravity = applyForAntigravity(registryPath, projectDir);
+    } else if (TOOL_PATHS[tool]) {
+      // Only the desktop app writes TOML configs
+      console.log(`  ℹ ${TOOL_PATHS[tool].name} is applied by the desktop app - skipping`);
+      results[tool] = false;
     }
   }
 
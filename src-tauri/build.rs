@@ -1,3 +1,42 @@
 fn main() {
-    tauri_build::build()
+    // Commands are only available to windows whose capabilities allow them
+    let commands = tauri_build::AppManifest::new().commands(&[
+        "apply_config",
+        "apply_tools",
+        "effective_config",
+        "file_read",
+        "file_write",
+        "file_delete",
+        "file_copy",
+        "file_rename",
+        "file_create_dir",
+        "claude_init_project",
+        "mcp_server_tools",
+        "open_logs",
+        "registry_list",
+        "registry_add",
+        "registry_remove",
+        "rollback_supported",
+        "terminal_open",
+        "terminal_write",
+        "terminal_resize",
+        "terminal_close",
+        "answer_update",
+        "release_notes",
+        "cancel_update",
+        "check_for_updates",
+        "update_settings",
+        "set_update_settings",
+        "update_from_file",
+        "vault_status",
+        "vault_unlock",
+        "vault_lock",
+        "vault_list",
+        "vault_set",
+        "vault_remove",
+        "vault_rotate",
+        "watch_project",
+    ]);
+    tauri_build::try_build(tauri_build::Attributes::new().app_manifest(commands))
+        .expect("failed to run tauri-build");
 }
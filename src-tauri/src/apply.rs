@@ -0,0 +1,413 @@
+//! Generate tool configs from the merged config hierarchy
+//!
+//! Native counterpart of `apply()` and `applyForTools()` in `lib/apply.js`:
+//! merge the hierarchy, interpolate `${VAR}` from `.env` files and the secrets
+//! vault, then let each
+//! [`ToolTarget`] render its files. Everything is computed before anything is
+//! written, so a dry run can return the would-be files and a diff.
+
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::Manager;
+
+use crate::config::{self, MergedConfig};
+use crate::diff::unified_diff;
+use crate::env::{self, EnvVars, Interpolation};
+use crate::error::{Error, Result};
+use crate::preferences::Preferences;
+use crate::registry::{self, Registry};
+use crate::tools::{self, Claude, ToolTarget};
+use crate::util::write_atomic;
+use crate::vault::VaultState;
+
+/// Directories an apply reads from and writes to
+pub struct ApplyContext<'a> {
+    pub dir: &'a Path,
+    pub home: &'a Path,
+    pub registry_path: &'a Path,
+    /// Secrets of the unlocked vault, empty while it is locked
+    pub secrets: &'a EnvVars,
+    /// Lookup for variables set neither in `.env` files nor in the vault
+    pub process_env: env::Lookup,
+}
+
+/// A file `apply` wants to write
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub content: String,
+    /// Unified diff against the file on disk
+    pub diff: String,
+    pub created: bool,
+    pub changed: bool,
+}
+
+impl FileChange {
+    pub fn new(path: PathBuf, content: String) -> Result<Self> {
+        let previous = match std::fs::read_to_string(&path) {
+            Ok(previous) => Some(previous),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            diff: unified_diff(previous.as_deref().unwrap_or(""), &content),
+            created: previous.is_none(),
+            changed: previous.as_deref() != Some(content.as_str()),
+            path,
+            content,
+        })
+    }
+
+    /// Write the file if its content differs from what is on disk
+    pub fn write(&self) -> Result<()> {
+        if self.changed {
+            write_atomic(&self.path, &self.content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of an apply, or what it would do in a dry run
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyPlan {
+    pub tool: &'static str,
+    pub dir: PathBuf,
+    /// Config files merged, root first
+    pub layers: Vec<PathBuf>,
+    pub servers: Vec<String>,
+    pub files: Vec<FileChange>,
+    pub dry_run: bool,
+}
+
+impl ApplyPlan {
+    pub fn write(&self) -> Result<()> {
+        for file in &self.files {
+            file.write()?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolve included registry MCPs and inline servers into output entries
+///
+/// Included MCPs come first; inline servers override them by name and
+/// entries starting with `_` are skipped as comments.
+pub fn build_servers(
+    registry: &Registry,
+    merged: &MergedConfig,
+    env: &EnvVars,
+    fallback: env::Lookup,
+    mode: Interpolation,
+) -> Result<IndexMap<String, Value>> {
+    let mut servers = IndexMap::new();
+
+    for include in &merged.include {
+        let name = &include.value;
+        let server = registry
+            .mcp_servers
+            .get(name)
+            .ok_or_else(|| Error::MissingRegistryEntry(name.clone()))?;
+        let value = serde_json::to_value(server).map_err(|source| Error::InvalidJson {
+            path: include.source.clone(),
+            source,
+        })?;
+        servers.insert(name.clone(), env::interpolate(&value, env, fallback, mode)?);
+    }
+
+    for (name, server) in &merged.mcp_servers {
+        if name.starts_with('_') {
+            continue;
+        }
+        servers.insert(name.clone(), env::interpolate(&server.value, env, fallback, mode)?);
+    }
+
+    Ok(servers)
+}
+
+/// Compute a tool's output for `ctx.dir` without writing anything
+///
+/// With `strict`, variables that cannot be resolved are an error instead of
+/// being left for the tool to expand, or emptied for tools that cannot.
+pub fn plan(target: &dyn ToolTarget, ctx: &ApplyContext, strict: bool) -> Result<ApplyPlan> {
+    let paths = target.paths();
+    let layers = config::load_layers(target.find_configs(ctx))?;
+    if layers.is_empty() {
+        return Err(Error::NoConfig {
+            file: format!("{}/mcps.json", paths.project_folder),
+            dir: ctx.dir.to_path_buf(),
+        });
+    }
+
+    let registry = Registry::load(ctx.registry_path)?;
+    let merged = config::merge_configs(layers);
+
+    let mut env = EnvVars::new();
+    for file in target.env_files(ctx, &merged.layers) {
+        env.extend(env::load_env_file(&file)?);
+    }
+    // A secret moved into the vault is not shadowed by a stale `.env` copy
+    env.extend(ctx.secrets.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let mode = if strict {
+        Interpolation::Require
+    } else if paths.supports_env_interpolation {
+        Interpolation::Keep
+    } else {
+        Interpolation::Resolve
+    };
+    let servers = build_servers(&registry, &merged, &env, ctx.process_env, mode)?;
+    let files = target.render(ctx, &merged, &servers)?;
+
+    Ok(ApplyPlan {
+        tool: &paths.id,
+        dir: ctx.dir.to_path_buf(),
+        layers: merged.layers.iter().map(|l| l.config_path.clone()).collect(),
+        servers: servers.keys().cloned().collect(),
+        files,
+        dry_run: true,
+    })
+}
+
+/// Plan and, unless `dry_run`, write a tool's output
+fn run(target: &dyn ToolTarget, ctx: &ApplyContext, dry_run: bool, strict: bool) -> Result<ApplyPlan> {
+    let mut plan = plan(target, ctx, strict)?;
+    if !dry_run {
+        plan.write()?;
+        plan.dry_run = false;
+        log::info!(
+            "Applied {} MCP(s) for {} in {}: {}",
+            plan.servers.len(),
+            target.paths().name,
+            ctx.dir.display(),
+            plan.servers.join(", ")
+        );
+    }
+    Ok(plan)
+}
+
+/// Result of applying to one tool
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ToolOutcome {
+    Applied(ApplyPlan),
+    /// The project has no config for this tool
+    Skipped { reason: String },
+    Failed { error: Error },
+}
+
+/// Generate `.mcp.json` for a project
+///
+/// With `dry_run`, nothing is written and the plan shows what would change.
+#[tauri::command]
+pub fn apply_config(
+    app: tauri::AppHandle,
+    dir: String,
+    dry_run: Option<bool>,
+    strict: Option<bool>,
+) -> Result<ApplyPlan> {
+    let home = app.path().home_dir()?;
+    let registry_path = registry::registry_path(&app)?;
+    let dir = std::path::absolute(dir)?;
+    let secrets = app.state::<VaultState>().secrets(&home);
+    let ctx = ApplyContext {
+        dir: &dir,
+        home: &home,
+        registry_path: &registry_path,
+        secrets: &secrets,
+        process_env: env::process_var,
+    };
+
+    run(&Claude, &ctx, dry_run.unwrap_or(false), strict.unwrap_or(false))
+}
+
+/// Apply to each tool in turn; each succeeds or fails on its own, like
+/// `applyForTools`
+pub fn apply_tools_in(
+    ctx: &ApplyContext,
+    tools: &[String],
+    dry_run: bool,
+    strict: bool,
+) -> IndexMap<String, ToolOutcome> {
+    let mut outcomes = IndexMap::new();
+    for id in tools {
+        if outcomes.contains_key(id) {
+            continue;
+        }
+
+        let outcome = match tools::target(id) {
+            Ok(target) => match run(target, ctx, dry_run, strict) {
+                Ok(plan) => ToolOutcome::Applied(plan),
+                Err(e @ Error::NoConfig { .. }) if !target.requires_config() => {
+                    ToolOutcome::Skipped { reason: e.to_string() }
+                }
+                Err(error) => ToolOutcome::Failed { error },
+            },
+            Err(error) => ToolOutcome::Failed { error },
+        };
+        outcomes.insert(id.clone(), outcome);
+    }
+    outcomes
+}
+
+/// Apply the registry to several tools, `enabledTools` from the preferences
+/// by default
+#[tauri::command]
+pub fn apply_tools(
+    app: tauri::AppHandle,
+    dir: String,
+    tools: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    strict: Option<bool>,
+) -> Result<IndexMap<String, ToolOutcome>> {
+    let home = app.path().home_dir()?;
+    let preferences = Preferences::load(&home);
+    let registry_path = preferences.registry_path(&home);
+    let dir = std::path::absolute(dir)?;
+    let secrets = app.state::<VaultState>().secrets(&home);
+    let ctx = ApplyContext {
+        dir: &dir,
+        home: &home,
+        registry_path: &registry_path,
+        secrets: &secrets,
+        process_env: env::process_var,
+    };
+
+    let tools = tools.unwrap_or(preferences.enabled_tools);
+    Ok(apply_tools_in(
+        &ctx,
+        &tools,
+        dry_run.unwrap_or(false),
+        strict.unwrap_or(false),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A home with a registry and a project including one of its MCPs
+    struct Fixture {
+        _tmp: tempfile::TempDir,
+        home: PathBuf,
+        project: PathBuf,
+        registry_path: PathBuf,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let tmp = tempfile::tempdir().unwrap();
+            let home = tmp.path().join("home");
+            let project = home.join("project");
+            let registry_path = home.join(".claude").join("registry.json");
+            std::fs::create_dir_all(project.join(".claude")).unwrap();
+            std::fs::create_dir_all(registry_path.parent().unwrap()).unwrap();
+
+            let registry = json!({
+                "mcpServers": {
+                    "github": { "command": "npx", "env": { "GITHUB_TOKEN": "${GITHUB_TOKEN}" } }
+                }
+            });
+            std::fs::write(&registry_path, registry.to_string()).unwrap();
+            let config = json!({
+                "include": ["github"],
+                "mcpServers": { "local": { "command": "node", "args": ["server.js"] }, "_note": {} }
+            });
+            std::fs::write(project.join(".claude").join("mcps.json"), config.to_string()).unwrap();
+            std::fs::write(project.join(".claude").join(".env"), "GITHUB_TOKEN=from-env\n").unwrap();
+
+            Self {
+                _tmp: tmp,
+                home,
+                project,
+                registry_path,
+            }
+        }
+
+        fn run(&self, secrets: &EnvVars, dry_run: bool, strict: bool) -> Result<ApplyPlan> {
+            let ctx = ApplyContext {
+                dir: &self.project,
+                home: &self.home,
+                registry_path: &self.registry_path,
+                secrets,
+                process_env: |_| None,
+            };
+            run(&Claude, &ctx, dry_run, strict)
+        }
+
+        fn output(&self) -> PathBuf {
+            self.project.join(".mcp.json")
+        }
+    }
+
+    #[test]
+    fn dry_run_reports_a_new_file_without_writing_it() {
+        let fixture = Fixture::new();
+        let plan = fixture.run(&EnvVars::new(), true, false).unwrap();
+
+        assert!(plan.dry_run);
+        assert_eq!(plan.servers, ["github", "local"]);
+        assert_eq!(plan.layers, [fixture.project.join(".claude").join("mcps.json")]);
+        let file = &plan.files[0];
+        assert_eq!(file.path, fixture.output());
+        assert!(file.created && file.changed);
+        assert!(file.diff.starts_with("@@ -0,0 +1,"));
+        assert!(file.content.contains("\"GITHUB_TOKEN\": \"from-env\""));
+        assert!(!fixture.output().exists());
+    }
+
+    #[test]
+    fn dry_run_diffs_against_the_file_on_disk() {
+        let fixture = Fixture::new();
+        std::fs::write(fixture.output(), "{\n  \"mcpServers\": {}\n}\n").unwrap();
+
+        let plan = fixture.run(&EnvVars::new(), true, false).unwrap();
+        let file = &plan.files[0];
+        assert!(!file.created && file.changed);
+        assert!(file.diff.contains("-  \"mcpServers\": {}\n"));
+        assert_eq!(
+            std::fs::read_to_string(fixture.output()).unwrap(),
+            "{\n  \"mcpServers\": {}\n}\n"
+        );
+    }
+
+    #[test]
+    fn apply_writes_and_a_second_dry_run_has_nothing_to_do() {
+        let fixture = Fixture::new();
+        let plan = fixture.run(&EnvVars::new(), false, false).unwrap();
+        assert!(!plan.dry_run);
+        assert_eq!(std::fs::read_to_string(fixture.output()).unwrap(), plan.files[0].content);
+
+        let plan = fixture.run(&EnvVars::new(), true, false).unwrap();
+        assert!(!plan.files[0].changed);
+        assert_eq!(plan.files[0].diff, "");
+    }
+
+    #[test]
+    fn strict_fails_on_unresolved_variables() {
+        let fixture = Fixture::new();
+        std::fs::remove_file(fixture.project.join(".claude").join(".env")).unwrap();
+
+        let err = fixture.run(&EnvVars::new(), true, true).unwrap_err();
+        assert!(matches!(err, Error::UnresolvedVariable(name) if name == "GITHUB_TOKEN"));
+        // Without strict, Claude Code expands it at launch
+        let plan = fixture.run(&EnvVars::new(), true, false).unwrap();
+        assert!(plan.files[0].content.contains("${GITHUB_TOKEN}"));
+    }
+
+    #[test]
+    fn missing_config_is_reported() {
+        let fixture = Fixture::new();
+        std::fs::remove_file(fixture.project.join(".claude").join("mcps.json")).unwrap();
+        assert!(matches!(
+            fixture.run(&EnvVars::new(), true, false),
+            Err(Error::NoConfig { .. })
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+//! Re-apply when config changes
+//!
+//! With `autoApply` in the preferences, a change to an `mcps.json`, a tool
+//! `.env` or the registry regenerates the output of every enabled tool, and a
+//! desktop notification sums up the result, so nobody has to remember to run
+//! `claude-config apply` after an edit.
+
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::apply::{self, ApplyContext, ToolOutcome};
+use crate::env;
+use crate::error::Error;
+use crate::preferences::Preferences;
+use crate::tools;
+use crate::vault::VaultState;
+
+/// Whether a changed file is an input of `apply`
+fn is_apply_input(path: &Path, registry_path: &Path) -> bool {
+    path == registry_path
+        || matches!(
+            path.file_name().and_then(|n| n.to_str()),
+            Some("mcps.json" | ".env")
+        )
+}
+
+/// Called by the watcher after each burst of changes in `dir`
+pub fn on_config_changed(app: &tauri::AppHandle, dir: &Path, paths: &[PathBuf]) {
+    let Ok(home) = app.path().home_dir() else {
+        return;
+    };
+    let preferences = Preferences::load(&home);
+    if !preferences.auto_apply {
+        return;
+    }
+
+    let registry_path = preferences.registry_path(&home);
+    if !paths.iter().any(|p| is_apply_input(p, &registry_path)) {
+        return;
+    }
+
+    let secrets = app.state::<VaultState>().secrets(&home);
+    let ctx = ApplyContext {
+        dir,
+        home: &home,
+        registry_path: &registry_path,
+        secrets: &secrets,
+        process_env: env::process_var,
+    };
+    let mut outcomes = apply::apply_tools_in(&ctx, &preferences.enabled_tools, false, false);
+    skip_missing_config(&mut outcomes);
+
+    if let Some((title, body)) = summary(&outcomes) {
+        notify(app, title, &body);
+    }
+}
+
+/// A project set up for some tools only is not an error when an edit
+/// triggers the apply, even for tools that otherwise require a config
+fn skip_missing_config(outcomes: &mut IndexMap<String, ToolOutcome>) {
+    for outcome in outcomes.values_mut() {
+        if let ToolOutcome::Failed {
+            error: error @ Error::NoConfig { .. },
+        } = outcome
+        {
+            *outcome = ToolOutcome::Skipped {
+                reason: error.to_string(),
+            };
+        }
+    }
+}
+
+pub fn notify(app: &tauri::AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Notification text, or `None` when nothing changed or failed
+pub fn summary(outcomes: &IndexMap<String, ToolOutcome>) -> Option<(&'static str, String)> {
+    let mut failed = false;
+    let mut lines = Vec::new();
+
+    for (id, outcome) in outcomes {
+        let name = tools::target(id).map(|t| t.paths().name.as_str()).unwrap_or(id.as_str());
+        match outcome {
+            ToolOutcome::Applied(plan) if plan.files.iter().any(|f| f.changed) => {
+                lines.push(format!("{}: {} MCP(s)", name, plan.servers.len()));
+            }
+            ToolOutcome::Failed { error } => {
+                failed = true;
+                log::warn!("Auto-apply failed for {}: {}", name, error);
+                lines.push(format!("{}: {}", name, error));
+            }
+            _ => {}
+        }
+    }
+
+    if lines.is_empty() {
+        return None;
+    }
+    let title = if failed {
+        "Config apply failed"
+    } else {
+        "Config applied"
+    };
+    Some((title, lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_config() -> ToolOutcome {
+        ToolOutcome::Failed {
+            error: Error::NoConfig {
+                file: ".claude/mcps.json".to_string(),
+                dir: PathBuf::from("/work/app"),
+            },
+        }
+    }
+
+    #[test]
+    fn missing_config_is_skipped_not_failed() {
+        let mut outcomes = IndexMap::from([
+            ("claude".to_string(), no_config()),
+            (
+                "gemini".to_string(),
+                ToolOutcome::Failed {
+                    error: Error::MissingRegistryEntry("github".to_string()),
+                },
+            ),
+        ]);
+        skip_missing_config(&mut outcomes);
+
+        assert!(matches!(outcomes["claude"], ToolOutcome::Skipped { .. }));
+        assert!(matches!(outcomes["gemini"], ToolOutcome::Failed { .. }));
+    }
+
+    #[test]
+    fn skipped_tools_are_left_out_of_the_notification() {
+        let mut outcomes = IndexMap::from([("claude".to_string(), no_config())]);
+        assert_eq!(summary(&outcomes).unwrap().0, "Config apply failed");
+
+        skip_missing_config(&mut outcomes);
+        assert_eq!(summary(&outcomes), None);
+    }
+}
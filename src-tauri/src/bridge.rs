@@ -0,0 +1,284 @@
+//! Requests from the server sidecar to the desktop host
+//!
+//! Inside the desktop app the host owns updates, process launches and file
+//! operations on paths from the UI, so the server's endpoints ask it instead
+//! of running npm, `claude /init` or MCP servers, or touching those files,
+//! themselves. The server is a child of the
+//! host and asks over its own pipes: a stdout line starting with `@desktop `
+//! carries a JSON request, and the answer goes back to its stdin as one JSON
+//! line with the same `id`. `ui/routes/desktop-bridge.js` is the other end.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::Manager;
+
+use crate::error::{Error, Result};
+use crate::files::{self, Caller};
+use crate::launch;
+use crate::sidecar::ServerSupervisor;
+use crate::updater;
+
+/// Marks a stdout line of the server as a request
+const PREFIX: &str = "@desktop ";
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// A request the host knows, with its parameters checked
+#[derive(Debug, PartialEq)]
+enum Call {
+    CheckForUpdates,
+    InstallUpdate,
+    ClaudeInit { dir: String },
+    McpServerTools { name: String },
+    RestartServer,
+    StatFile { path: PathBuf },
+    ReadFile { path: PathBuf },
+    WriteFile { path: PathBuf, content: String },
+    DeleteFile { path: PathBuf },
+    CopyFile { source: PathBuf, target: PathBuf },
+    RenameFile { source: PathBuf, target: PathBuf },
+    CreateDir { path: PathBuf },
+}
+
+impl Call {
+    fn parse(method: &str, params: &Value) -> Result<Self> {
+        Ok(match method {
+            // `/api/version-check`: report without prompting
+            "checkForUpdates" => Self::CheckForUpdates,
+            // `/api/update`: the usual release notes window takes over
+            "installUpdate" => Self::InstallUpdate,
+            // `/api/projects` with `runClaudeInit`
+            "claudeInit" => Self::ClaudeInit {
+                dir: param(params, "dir")?,
+            },
+            // `/api/mcp-server-tools`
+            "mcpServerTools" => Self::McpServerTools {
+                name: param(params, "name")?,
+            },
+            // `/api/restart`: the server exits once this is answered
+            "restartServer" => Self::RestartServer,
+            // File explorer routes, confined to the sandbox
+            "statFile" => Self::StatFile {
+                path: path_param(params, "path")?,
+            },
+            "readFile" => Self::ReadFile {
+                path: path_param(params, "path")?,
+            },
+            "writeFile" => Self::WriteFile {
+                path: path_param(params, "path")?,
+                content: param(params, "content")?,
+            },
+            "deleteFile" => Self::DeleteFile {
+                path: path_param(params, "path")?,
+            },
+            "copyFile" => Self::CopyFile {
+                source: path_param(params, "source")?,
+                target: path_param(params, "target")?,
+            },
+            "renameFile" => Self::RenameFile {
+                source: path_param(params, "source")?,
+                target: path_param(params, "target")?,
+            },
+            "createDir" => Self::CreateDir {
+                path: path_param(params, "path")?,
+            },
+            _ => return Err(Error::Invalid(format!("Unknown request \"{}\"", method))),
+        })
+    }
+}
+
+/// The request in a stdout line of the server, `None` for its own output
+pub fn request_json(line: &str) -> Option<&str> {
+    line.strip_prefix(PREFIX).map(str::trim_end)
+}
+
+fn parse_request(json: &str) -> Result<Request> {
+    serde_json::from_str(json).map_err(|e| Error::Invalid(format!("Invalid request from server: {}", e)))
+}
+
+/// The answer to request `id`, as the line the server reads
+fn reply(id: u64, result: Result<Value>) -> String {
+    let response = match result {
+        Ok(result) => Response {
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(e) => Response {
+            id,
+            result: None,
+            error: Some(e.to_string()),
+        },
+    };
+    serde_json::to_string(&response).expect("response serializes")
+}
+
+/// Answer a request from [`request_json`], without blocking the server's
+/// output
+pub fn handle(app: &tauri::AppHandle, json: &str) {
+    let request = match parse_request(json) {
+        Ok(request) => request,
+        Err(e) => {
+            // Without an id there is nobody to answer
+            log::warn!("{}", e);
+            return;
+        }
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        log::debug!("Server request {}: {}", request.id, request.method);
+        let result = match Call::parse(&request.method, &request.params) {
+            Ok(call) => dispatch(&app, call).await,
+            Err(e) => Err(e),
+        };
+
+        let line = reply(request.id, result);
+        if let Err(e) = app.state::<ServerSupervisor>().write_line(&line) {
+            log::warn!("Failed to answer server request {}: {}", request.id, e);
+        }
+    });
+}
+
+async fn dispatch(app: &tauri::AppHandle, call: Call) -> Result<Value> {
+    match call {
+        Call::CheckForUpdates => to_value(updater::find_update(app).await?),
+        Call::InstallUpdate => to_value(updater::check_for_updates(app.clone()).await?),
+        Call::ClaudeInit { dir } => {
+            launch::claude_init_project(dir).await?;
+            Ok(Value::Null)
+        }
+        Call::McpServerTools { name } => to_value(launch::mcp_server_tools(app.clone(), name).await?),
+        Call::RestartServer => {
+            app.state::<ServerSupervisor>().request_restart();
+            Ok(Value::Null)
+        }
+        Call::StatFile { path } => {
+            to_value(files::sandboxed(app, Caller::Server, |sandbox| sandbox.stat(&path))?)
+        }
+        Call::ReadFile { path } => {
+            to_value(files::sandboxed(app, Caller::Server, |sandbox| sandbox.read(&path))?)
+        }
+        Call::WriteFile { path, content } => {
+            files::sandboxed(app, Caller::Server, |sandbox| sandbox.write(&path, &content))?;
+            Ok(Value::Null)
+        }
+        Call::DeleteFile { path } => {
+            files::sandboxed(app, Caller::Server, |sandbox| sandbox.delete(&path))?;
+            Ok(Value::Null)
+        }
+        Call::CopyFile { source, target } => {
+            files::sandboxed(app, Caller::Server, |sandbox| sandbox.copy(&source, &target))?;
+            Ok(Value::Null)
+        }
+        Call::RenameFile { source, target } => {
+            files::sandboxed(app, Caller::Server, |sandbox| sandbox.rename(&source, &target))?;
+            Ok(Value::Null)
+        }
+        Call::CreateDir { path } => {
+            files::sandboxed(app, Caller::Server, |sandbox| sandbox.create_dir(&path))?;
+            Ok(Value::Null)
+        }
+    }
+}
+
+fn param(params: &Value, name: &str) -> Result<String> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| Error::Invalid(format!("Missing parameter \"{}\"", name)))
+}
+
+fn path_param(params: &Value, name: &str) -> Result<PathBuf> {
+    param(params, name).map(PathBuf::from)
+}
+
+fn to_value<T: Serialize>(result: T) -> Result<Value> {
+    serde_json::to_value(result).map_err(|e| Error::Invalid(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn only_prefixed_lines_are_requests() {
+        assert_eq!(request_json("@desktop {\"id\":1}\r\n"), Some("{\"id\":1}"));
+        assert_eq!(request_json("Server listening on 3333"), None);
+        assert_eq!(request_json("@desktop{\"id\":1}"), None);
+        assert_eq!(request_json(" @desktop {\"id\":1}"), None);
+    }
+
+    #[test]
+    fn requests_are_parsed() {
+        let request = parse_request(r#"{"id":7,"method":"readFile","params":{"path":"/p/a.md"}}"#).unwrap();
+        assert_eq!((request.id, request.method.as_str()), (7, "readFile"));
+        assert_eq!(
+            Call::parse(&request.method, &request.params).unwrap(),
+            Call::ReadFile {
+                path: PathBuf::from("/p/a.md")
+            }
+        );
+
+        // `params` may be left out
+        let request = parse_request(r#"{"id":8,"method":"restartServer"}"#).unwrap();
+        assert_eq!(Call::parse(&request.method, &request.params).unwrap(), Call::RestartServer);
+    }
+
+    #[test]
+    fn malformed_json_is_refused() {
+        for json in ["{not json", r#"{"method":"readFile"}"#, r#"{"id":"1","method":"readFile"}"#, ""] {
+            let err = parse_request(json).unwrap_err().to_string();
+            assert!(err.starts_with("Invalid request from server"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn unknown_methods_and_missing_parameters_are_errors() {
+        let err = Call::parse("runShell", &json!({ "command": "rm" })).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown request \"runShell\"");
+
+        let err = Call::parse("copyFile", &json!({ "source": "/a" })).unwrap_err();
+        assert_eq!(err.to_string(), "Missing parameter \"target\"");
+        let err = Call::parse("writeFile", &json!({ "path": "/a", "content": 1 })).unwrap_err();
+        assert_eq!(err.to_string(), "Missing parameter \"content\"");
+    }
+
+    #[test]
+    fn replies_are_single_lines_with_the_request_id() {
+        let line = reply(3, Ok(json!({ "content": "a\nb" })));
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<Value>(&line).unwrap(),
+            json!({ "id": 3, "result": { "content": "a\nb" } })
+        );
+
+        let line = reply(4, Ok(Value::Null));
+        assert_eq!(serde_json::from_str::<Value>(&line).unwrap(), json!({ "id": 4, "result": null }));
+
+        let line = reply(5, Err(Error::Invalid("Unknown request \"x\"".to_string())));
+        assert_eq!(
+            serde_json::from_str::<Value>(&line).unwrap(),
+            json!({ "id": 5, "error": "Unknown request \"x\"" })
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Command line of the desktop app
+//!
+//! `claude-config-desktop [PROJECT] [--port PORT] [--headless] [--profile NAME]`
+//!
+//! The project directory and port are passed on to the server, like the
+//! arguments of `claude-config ui`. The profile is a workstream, matched by
+//! id or name as in `claude-config workstream use`. Instead of a project,
+//! Windows and Linux pass a `claude-config://` link the app was opened with.
+//!
+//! `claude-config-desktop secrets ...` manages the secrets vault instead of
+//! starting the app.
+
+use std::path::{Path, PathBuf};
+
+use tauri::Manager;
+
+use crate::deep_link;
+use crate::error::{Error, Result};
+use crate::workstreams::Workstreams;
+
+pub const USAGE: &str = "\
+Usage: claude-config-desktop [PROJECT] [OPTIONS]
+       claude-config-desktop secrets <list|set NAME|remove NAME|rotate>
+
+Arguments:
+  [PROJECT]            Project directory to open
+
+Options:
+  -p, --port <PORT>    Port of the UI server
+      --headless       Run the server and tray icon without a window
+      --profile <NAME> Workstream to activate
+  -h, --help           Print this help";
+
+/// Arguments the app was launched with
+#[derive(Debug, Default, Clone)]
+pub struct LaunchArgs {
+    /// Project directory, absolute
+    pub dir: Option<PathBuf>,
+    pub port: Option<u16>,
+    pub headless: bool,
+    pub profile: Option<String>,
+    /// `claude-config://` link
+    pub url: Option<String>,
+    pub help: bool,
+    /// Arguments of the `secrets` subcommand
+    pub secrets: Option<Vec<String>>,
+}
+
+impl LaunchArgs {
+    /// Arguments of this process
+    pub fn from_env() -> Result<Self> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Self::parse(&args, &std::env::current_dir()?)
+    }
+
+    /// Parse the arguments after the executable, resolving a relative
+    /// project directory against `cwd`
+    pub fn parse(args: &[String], cwd: &Path) -> Result<Self> {
+        let mut parsed = Self::default();
+        // A project folder named like the subcommand needs `./secrets`
+        if let Some(("secrets", rest)) = args.split_first().map(|(first, rest)| (first.as_str(), rest)) {
+            parsed.secrets = Some(rest.to_vec());
+            return Ok(parsed);
+        }
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next().cloned())
+                    .ok_or_else(|| Error::Invalid(format!("{} requires a value", name)))
+            };
+
+            match name {
+                "--port" | "-p" => {
+                    let port = value()?;
+                    parsed.port = match port.parse::<u16>() {
+                        Ok(port) if port > 0 => Some(port),
+                        _ => return Err(Error::Invalid(format!("Invalid port: {}", port))),
+                    };
+                }
+                "--profile" => parsed.profile = Some(value()?),
+                "--headless" => parsed.headless = true,
+                "--help" | "-h" => parsed.help = true,
+                // macOS adds a process serial number when launched from Finder
+                _ if name.starts_with("-psn_") => {}
+                _ if name.starts_with('-') => {
+                    return Err(Error::Invalid(format!("Unknown option: {}", name)));
+                }
+                _ if arg.starts_with(&format!("{}://", deep_link::SCHEME)) => {
+                    parsed.url = Some(arg.clone());
+                }
+                _ if parsed.dir.is_some() => {
+                    return Err(Error::Invalid(format!("Unexpected argument: {}", arg)));
+                }
+                _ => parsed.dir = Some(project_dir(cwd, arg)?),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Activate the workstream named by `--profile`
+pub fn use_profile(app: &tauri::AppHandle, profile: &str) {
+    let result = app
+        .path()
+        .home_dir()
+        .map_err(Into::into)
+        .and_then(|home| Workstreams::activate(&home, profile));
+    if let Err(e) = result {
+        log::error!("Failed to use profile: {}", e);
+    }
+}
+
+fn project_dir(cwd: &Path, arg: &str) -> Result<PathBuf> {
+    let path = cwd.join(arg);
+    if !path.is_dir() {
+        return Err(Error::Invalid(format!("Not a directory: {}", path.display())));
+    }
+    Ok(std::fs::canonicalize(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str], cwd: &Path) -> Result<LaunchArgs> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        LaunchArgs::parse(&args, cwd)
+    }
+
+    #[test]
+    fn port_is_parsed_in_every_form() {
+        let cwd = Path::new("/");
+        for args in [&["--port", "4000"][..], &["--port=4000"], &["-p", "4000"]] {
+            assert_eq!(parse(args, cwd).unwrap().port, Some(4000));
+        }
+        assert!(parse(&["--port"], cwd).is_err());
+        assert!(parse(&["--port", "0"], cwd).is_err());
+        assert!(parse(&["--port", "99999"], cwd).is_err());
+        assert!(parse(&["-p", "http"], cwd).is_err());
+    }
+
+    #[test]
+    fn flags_and_profile_are_parsed() {
+        let args = parse(&["--headless", "--profile", "client work", "-h"], Path::new("/")).unwrap();
+        assert!(args.headless && args.help);
+        assert_eq!(args.profile.as_deref(), Some("client work"));
+        assert_eq!(
+            parse(&["--profile=Research"], Path::new("/")).unwrap().profile.as_deref(),
+            Some("Research")
+        );
+        assert!(parse(&["--verbose"], Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn finder_process_serial_number_is_ignored() {
+        let args = parse(&["-psn_0_123456"], Path::new("/")).unwrap();
+        assert!(args.dir.is_none() && args.url.is_none());
+    }
+
+    #[test]
+    fn deep_link_is_kept_as_url() {
+        let url = "claude-config://open?project=~/app";
+        let args = parse(&[url], Path::new("/")).unwrap();
+        assert_eq!(args.url.as_deref(), Some(url));
+        assert!(args.dir.is_none());
+    }
+
+    #[test]
+    fn project_dir_is_resolved_against_cwd() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("app")).unwrap();
+        let expected = std::fs::canonicalize(tmp.path().join("app")).unwrap();
+
+        assert_eq!(parse(&["app"], tmp.path()).unwrap().dir, Some(expected.clone()));
+        assert_eq!(parse(&["./app/", "-p", "4000"], tmp.path()).unwrap().dir, Some(expected));
+        assert!(parse(&["missing"], tmp.path()).is_err());
+        assert!(parse(&["app", "app"], tmp.path()).is_err());
+    }
+
+    #[test]
+    fn secrets_subcommand_takes_the_remaining_arguments() {
+        let args = parse(&["secrets", "set", "--port"], Path::new("/")).unwrap();
+        assert_eq!(args.secrets, Some(vec!["set".to_string(), "--port".to_string()]));
+        assert!(args.port.is_none());
+
+        assert_eq!(parse(&["secrets"], Path::new("/")).unwrap().secrets, Some(vec![]));
+    }
+
+    #[test]
+    fn secrets_is_only_a_subcommand_in_first_position() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join("secrets")).unwrap();
+
+        let args = parse(&["--headless", "secrets"], tmp.path()).unwrap();
+        assert!(args.secrets.is_none());
+        assert_eq!(args.dir, Some(std::fs::canonicalize(tmp.path().join("secrets")).unwrap()));
+        assert!(parse(&["./secrets"], tmp.path()).unwrap().secrets.is_none());
+    }
+}
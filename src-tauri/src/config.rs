@@ -0,0 +1,314 @@
+//! Hierarchical `.claude/mcps.json` discovery and merging
+//!
+//! Native counterparts of `findAllConfigs` and `mergeConfigs` in
+//! `lib/config.js`. The merge also records which file set each value, so the
+//! UI can show the effective config together with its provenance.
+
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::Manager;
+
+use crate::error::Result;
+use crate::util::load_json;
+
+/// Contents of a `.claude/mcps.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct McpsConfig {
+    pub include: Vec<String>,
+    /// Inline servers; keys starting with `_` are comments, not servers
+    pub mcp_servers: IndexMap<String, Value>,
+    pub enabled_plugins: IndexMap<String, bool>,
+    pub template: Option<String>,
+}
+
+/// A config file found in the hierarchy, root first
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLayer {
+    pub dir: PathBuf,
+    pub config_path: PathBuf,
+    #[serde(skip)]
+    pub config: McpsConfig,
+}
+
+/// A merged value and the config file it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct Sourced<T> {
+    pub value: T,
+    pub source: PathBuf,
+}
+
+/// Result of merging every layer, child overriding parent
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedConfig {
+    pub layers: Vec<ConfigLayer>,
+    /// Included registry MCPs, attributed to the first layer that listed them
+    pub include: Vec<Sourced<String>>,
+    pub mcp_servers: IndexMap<String, Sourced<Value>>,
+    /// `false` disables a plugin enabled by a parent
+    pub enabled_plugins: IndexMap<String, Sourced<bool>>,
+    pub template: Option<Sourced<String>>,
+}
+
+/// Find every `.claude/mcps.json` from `start_dir` up to the filesystem root,
+/// plus `~/.claude/mcps.json`, ordered root to leaf
+pub fn find_all_configs(start_dir: &Path, home: &Path) -> Vec<(PathBuf, PathBuf)> {
+    let mut configs = Vec::new();
+
+    // Like `findAllConfigs`, the filesystem root itself is not searched
+    let mut dir = Some(start_dir);
+    while let Some(current) = dir.filter(|d| d.parent().is_some()) {
+        let config_path = current.join(".claude").join("mcps.json");
+        if config_path.is_file() {
+            configs.insert(0, (current.to_path_buf(), config_path));
+        }
+        dir = current.parent();
+    }
+
+    let home_config = home.join(".claude").join("mcps.json");
+    if home_config.is_file() && !configs.iter().any(|(_, path)| *path == home_config) {
+        configs.insert(0, (home.to_path_buf(), home_config));
+    }
+
+    configs
+}
+
+/// Find every `<project_folder>/mcps.json` from `start_dir` up to, but not
+/// including, the home directory, plus the tool's global config, ordered root
+/// to leaf
+///
+/// Mirrors `findAllConfigsForTool`, used for tools other than Claude Code.
+pub fn find_tool_configs(
+    project_folder: &str,
+    global_config: Option<&Path>,
+    start_dir: &Path,
+    home: &Path,
+) -> Vec<(PathBuf, PathBuf)> {
+    let mut configs = Vec::new();
+
+    let mut dir = Some(start_dir);
+    while let Some(current) = dir.filter(|d| d.parent().is_some() && *d != home) {
+        let config_path = current.join(project_folder).join("mcps.json");
+        if config_path.is_file() {
+            configs.insert(0, (current.to_path_buf(), config_path));
+        }
+        dir = current.parent();
+    }
+
+    if let Some(global_config) = global_config.filter(|p| p.is_file()) {
+        configs.insert(0, (home.to_path_buf(), global_config.to_path_buf()));
+    }
+
+    configs
+}
+
+/// Parse configs found by [`find_all_configs`] or [`find_tool_configs`]
+pub fn load_layers(configs: Vec<(PathBuf, PathBuf)>) -> Result<Vec<ConfigLayer>> {
+    configs
+        .into_iter()
+        .map(|(dir, config_path)| {
+            let config = load_json(&config_path)?.unwrap_or_default();
+            Ok(ConfigLayer {
+                dir,
+                config_path,
+                config,
+            })
+        })
+        .collect()
+}
+
+/// Merge layers in order, later ones overriding earlier
+pub fn merge_configs(layers: Vec<ConfigLayer>) -> MergedConfig {
+    let mut merged = MergedConfig::default();
+
+    for layer in &layers {
+        let source = || layer.config_path.clone();
+        let config = &layer.config;
+
+        for name in &config.include {
+            if !merged.include.iter().any(|i| i.value == *name) {
+                merged.include.push(Sourced {
+                    value: name.clone(),
+                    source: source(),
+                });
+            }
+        }
+
+        for (name, server) in &config.mcp_servers {
+            merged.mcp_servers.insert(
+                name.clone(),
+                Sourced {
+                    value: server.clone(),
+                    source: source(),
+                },
+            );
+        }
+
+        for (plugin, enabled) in &config.enabled_plugins {
+            merged.enabled_plugins.insert(
+                plugin.clone(),
+                Sourced {
+                    value: *enabled,
+                    source: source(),
+                },
+            );
+        }
+
+        if let Some(template) = &config.template {
+            merged.template = Some(Sourced {
+                value: template.clone(),
+                source: source(),
+            });
+        }
+    }
+
+    merged.layers = layers;
+    merged
+}
+
+/// Effective MCP config for a directory, with the file behind each value
+#[tauri::command]
+pub fn effective_config(app: tauri::AppHandle, dir: String) -> Result<MergedConfig> {
+    let home = app.path().home_dir()?;
+    let dir = std::path::absolute(dir)?;
+    let layers = load_layers(find_all_configs(&dir, &home))?;
+    Ok(merge_configs(layers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write_config(dir: &Path, folder: &str, config: Value) -> PathBuf {
+        let path = dir.join(folder).join("mcps.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, config.to_string()).unwrap();
+        path
+    }
+
+    fn paths(configs: &[(PathBuf, PathBuf)]) -> Vec<&Path> {
+        configs.iter().map(|(_, path)| path.as_path()).collect()
+    }
+
+    #[test]
+    fn configs_are_found_root_to_leaf() {
+        let tmp = tempfile::tempdir().unwrap();
+        let home = tmp.path().join("home");
+        let project = home.join("work").join("app");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        let home_config = write_config(&home, ".claude", json!({}));
+        let work_config = write_config(&home.join("work"), ".claude", json!({}));
+        let app_config = write_config(&project, ".claude", json!({}));
+
+        let configs = find_all_configs(&project.join("src"), &home);
+        // The home config is on the way up and listed once
+        assert_eq!(paths(&configs), [&home_config, &work_config, &app_config]);
+        assert_eq!(configs[1].0, home.join("work"));
+    }
+
+    #[test]
+    fn home_config_comes_first_for_projects_outside_home() {
+        let tmp = tempfile::tempdir().unwrap();
+        let home = tmp.path().join("home");
+        let project = tmp.path().join("srv").join("app");
+        let home_config = write_config(&home, ".claude", json!({}));
+        let app_config = write_config(&project, ".claude", json!({}));
+
+        let configs = find_all_configs(&project, &home);
+        assert_eq!(paths(&configs), [&home_config, &app_config]);
+        assert_eq!(configs[0].0, home);
+    }
+
+    #[test]
+    fn tool_configs_stop_at_home_and_start_with_the_global_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let home = tmp.path().join("home");
+        let project = home.join("work").join("app");
+        // Not found: the walk stops below home
+        write_config(&home, ".gemini", json!({}));
+        let work_config = write_config(&home.join("work"), ".gemini", json!({}));
+        let app_config = write_config(&project, ".gemini", json!({}));
+        let global = home.join(".gemini").join("settings.json");
+        std::fs::write(&global, "{}").unwrap();
+
+        let configs = find_tool_configs(".gemini", Some(&global), &project, &home);
+        assert_eq!(paths(&configs), [&global, &work_config, &app_config]);
+
+        let missing = home.join(".gemini").join("missing.json");
+        let configs = find_tool_configs(".gemini", Some(&missing), &project, &home);
+        assert_eq!(paths(&configs), [&work_config, &app_config]);
+    }
+
+    #[test]
+    fn merge_lets_children_override_and_records_sources() {
+        let tmp = tempfile::tempdir().unwrap();
+        let parent = tmp.path().join("work");
+        let child = parent.join("app");
+        let parent_config = write_config(
+            &parent,
+            ".claude",
+            json!({
+                "include": ["github", "postgres"],
+                "mcpServers": { "shared": { "command": "old" }, "docs": { "command": "docs" } },
+                "enabledPlugins": { "lint": true, "format": true },
+                "template": "node"
+            }),
+        );
+        let child_config = write_config(
+            &child,
+            ".claude",
+            json!({
+                "include": ["postgres", "slack"],
+                "mcpServers": { "shared": { "command": "new" } },
+                "enabledPlugins": { "format": false }
+            }),
+        );
+
+        let layers = load_layers(find_all_configs(&child, tmp.path())).unwrap();
+        let merged = merge_configs(layers);
+
+        let include: Vec<(&str, &Path)> = merged
+            .include
+            .iter()
+            .map(|i| (i.value.as_str(), i.source.as_path()))
+            .collect();
+        assert_eq!(
+            include,
+            [
+                ("github", parent_config.as_path()),
+                ("postgres", parent_config.as_path()),
+                ("slack", child_config.as_path()),
+            ]
+        );
+
+        assert_eq!(merged.mcp_servers["shared"].value, json!({ "command": "new" }));
+        assert_eq!(merged.mcp_servers["shared"].source, child_config);
+        assert_eq!(merged.mcp_servers["docs"].source, parent_config);
+
+        assert!(merged.enabled_plugins["lint"].value);
+        assert!(!merged.enabled_plugins["format"].value);
+        assert_eq!(merged.enabled_plugins["format"].source, child_config);
+
+        // Not set by the child, so the parent's stays
+        let template = merged.template.unwrap();
+        assert_eq!((template.value.as_str(), template.source), ("node", parent_config));
+        assert_eq!(merged.layers.len(), 2);
+    }
+
+    #[test]
+    fn unreadable_layers_are_an_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".claude").join("mcps.json");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let result = load_layers(vec![(tmp.path().to_path_buf(), path)]);
+        assert!(matches!(result, Err(crate::error::Error::InvalidJson { .. })));
+    }
+}
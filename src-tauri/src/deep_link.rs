@@ -0,0 +1,315 @@
+//! `claude-config://` links
+//!
+//! Setup instructions in READMEs and chat can link straight into the app:
+//!
+//! - `claude-config://open?project=/path` switches to a project
+//! - `claude-config://registry/add?name=github&config={...}` adds an MCP to
+//!   the registry, with `config` the JSON of its `mcpServers` entry
+//!
+//! Anyone can send such a link, so it is validated strictly and the user
+//! confirms what it will do before anything is written.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{Manager, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::auto_apply;
+use crate::error::{Error, Result};
+use crate::instance;
+use crate::registry::{self, McpServer, Registry};
+use crate::util::expand_home;
+
+pub const SCHEME: &str = "claude-config";
+
+/// Longer links are rejected rather than shown in a dialog
+const MAX_URL_LEN: usize = 8192;
+
+#[derive(Debug)]
+enum Action {
+    Open { project: PathBuf },
+    AddToRegistry { name: String, server: Box<McpServer> },
+}
+
+/// Register the URL scheme and handle links opened while the app runs
+pub fn init(app: &tauri::AppHandle) {
+    // Installers register the scheme, but AppImages and development builds
+    // have to do it themselves
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = app.deep_link().register_all() {
+        log::warn!("Failed to register {}:// links: {}", SCHEME, e);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open_url(&handle, url.to_string());
+        }
+    });
+}
+
+/// Handle a link the app was launched with, before the server starts so
+/// that an opened project is the one it serves
+///
+/// On macOS links always arrive through [`init`]'s handler.
+pub fn handle_startup(app: &tauri::AppHandle) {
+    #[cfg(any(windows, target_os = "linux"))]
+    match app.deep_link().get_current() {
+        Ok(urls) => {
+            for url in urls.unwrap_or_default() {
+                handle(app, url.as_str());
+            }
+        }
+        Err(e) => log::warn!("Failed to read launch link: {}", e),
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    let _ = app;
+}
+
+/// Handle a link without blocking the caller on the confirmation dialog
+pub fn open_url(app: &tauri::AppHandle, url: String) {
+    let app = app.clone();
+    std::thread::spawn(move || handle(&app, &url));
+}
+
+fn handle(app: &tauri::AppHandle, url: &str) {
+    log::info!("Opening link {}", url);
+
+    let action = app
+        .path()
+        .home_dir()
+        .map_err(Into::into)
+        .and_then(|home| parse(url, &home));
+    let action = match action {
+        Ok(action) => action,
+        Err(e) => {
+            log::warn!("Rejected link {}: {}", url, e);
+            app.dialog()
+                .message(format!("This link cannot be opened:\n\n{}", e))
+                .kind(MessageDialogKind::Error)
+                .title("Invalid Link")
+                .blocking_show();
+            return;
+        }
+    };
+
+    if !confirm(app, &action) {
+        log::info!("Link declined");
+        return;
+    }
+
+    match action {
+        Action::Open { project } => instance::open_project(app, project),
+        Action::AddToRegistry { name, server } => {
+            let result = registry::registry_path(app)
+                .and_then(|path| registry::add(&path, &name, *server));
+            match result {
+                Ok(()) => auto_apply::notify(
+                    app,
+                    "MCP added",
+                    &format!("Added \"{}\" to the registry.", name),
+                ),
+                Err(e) => {
+                    log::error!("Failed to add \"{}\" to registry: {}", name, e);
+                    auto_apply::notify(app, "MCP not added", &e.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Ask before acting on a link, showing exactly what it will do
+fn confirm(app: &tauri::AppHandle, action: &Action) -> bool {
+    let (title, message, ok) = match action {
+        Action::Open { project } => (
+            "Open Project",
+            format!("A link asks to open the project:\n\n{}", project.display()),
+            "Open",
+        ),
+        Action::AddToRegistry { name, server } => {
+            let replaces = registry::registry_path(app)
+                .and_then(|path| Registry::load(&path))
+                .is_ok_and(|registry| registry.mcp_servers.contains_key(name));
+            let config = serde_json::to_string_pretty(server).unwrap_or_default();
+            let message = if replaces {
+                format!(
+                    "A link asks to replace \"{}\" in your MCP registry with:\n\n{}",
+                    name, config
+                )
+            } else {
+                format!("A link asks to add \"{}\" to your MCP registry:\n\n{}", name, config)
+            };
+            ("Add MCP", message, "Add")
+        }
+    };
+
+    app.dialog()
+        .message(message)
+        .kind(MessageDialogKind::Warning)
+        .title(title)
+        .buttons(MessageDialogButtons::OkCancelCustom(ok.to_string(), "Cancel".to_string()))
+        .blocking_show()
+}
+
+/// Validate a link and turn it into the action it asks for
+fn parse(url: &str, home: &Path) -> Result<Action> {
+    if url.len() > MAX_URL_LEN {
+        return Err(Error::Invalid(format!("Link is longer than {} characters", MAX_URL_LEN)));
+    }
+    let url = Url::parse(url).map_err(|e| Error::Invalid(format!("Invalid link: {}", e)))?;
+    if url.scheme() != SCHEME {
+        return Err(Error::Invalid(format!("Not a {}:// link", SCHEME)));
+    }
+
+    let route = format!("{}{}", url.host_str().unwrap_or_default(), url.path());
+    match route.trim_end_matches('/') {
+        "open" => {
+            let [project] = query(&url, ["project"])?;
+            Ok(Action::Open {
+                project: project_dir(&project, home)?,
+            })
+        }
+        "registry/add" => {
+            let [name, config] = query(&url, ["name", "config"])?;
+            let name = name.trim().to_string();
+            let server: McpServer = serde_json::from_str(&config)
+                .map_err(|e| Error::Invalid(format!("Invalid MCP config: {}", e)))?;
+            registry::validate_name(&name)?;
+            server.validate()?;
+            Ok(Action::AddToRegistry {
+                name,
+                server: Box::new(server),
+            })
+        }
+        route => Err(Error::Invalid(format!("Unknown link \"{}\"", route))),
+    }
+}
+
+/// Values of exactly these query parameters, each given once
+fn query<const N: usize>(url: &Url, names: [&str; N]) -> Result<[String; N]> {
+    let mut values: [Option<String>; N] = std::array::from_fn(|_| None);
+    for (key, value) in url.query_pairs() {
+        let Some(i) = names.iter().position(|name| *name == key) else {
+            return Err(Error::Invalid(format!("Unexpected parameter \"{}\"", key)));
+        };
+        if values[i].replace(value.into_owned()).is_some() {
+            return Err(Error::Invalid(format!("Parameter \"{}\" given twice", key)));
+        }
+    }
+
+    let mut result = Vec::with_capacity(N);
+    for (value, name) in values.into_iter().zip(names) {
+        let value = value.ok_or_else(|| Error::Invalid(format!("Missing parameter \"{}\"", name)))?;
+        result.push(value);
+    }
+    Ok(result.try_into().expect("one value per name"))
+}
+
+/// An existing project directory, given as an absolute or `~` path
+fn project_dir(project: &str, home: &Path) -> Result<PathBuf> {
+    let path = expand_home(project.trim(), home);
+    if !path.is_absolute() {
+        return Err(Error::Invalid(format!("Project path must be absolute: {}", project)));
+    }
+    if !path.is_dir() {
+        return Err(Error::Invalid(format!("Not a directory: {}", path.display())));
+    }
+    Ok(std::fs::canonicalize(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Link to `route` with the query parameters URL-encoded
+    fn link(route: &str, params: &[(&str, &str)]) -> String {
+        let mut url = Url::parse(&format!("{}://{}", SCHEME, route)).unwrap();
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        url.to_string()
+    }
+
+    fn error(url: &str, home: &Path) -> String {
+        parse(url, home).unwrap_err().to_string()
+    }
+
+    const GITHUB: &str = r#"{"command":"npx","args":["-y","@modelcontextprotocol/server-github"]}"#;
+
+    #[test]
+    fn open_resolves_absolute_and_home_projects() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir(home.path().join("app")).unwrap();
+        let expected = std::fs::canonicalize(home.path().join("app")).unwrap();
+
+        let absolute = home.path().join("app").to_string_lossy().into_owned();
+        for project in [absolute.as_str(), "~/app"] {
+            let action = parse(&link("open", &[("project", project)]), home.path()).unwrap();
+            assert!(matches!(action, Action::Open { project } if project == expected));
+        }
+    }
+
+    #[test]
+    fn open_rejects_relative_and_missing_projects() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::create_dir(home.path().join("app")).unwrap();
+
+        let relative = error(&link("open", &[("project", "app")]), home.path());
+        assert!(relative.contains("must be absolute"), "{}", relative);
+        let parent = error(&link("open", &[("project", "../app")]), home.path());
+        assert!(parent.contains("must be absolute"), "{}", parent);
+        let missing = home.path().join("missing").to_string_lossy().into_owned();
+        let missing = error(&link("open", &[("project", &missing)]), home.path());
+        assert!(missing.contains("Not a directory"), "{}", missing);
+    }
+
+    #[test]
+    fn registry_add_parses_the_server() {
+        let url = link("registry/add", &[("name", " github "), ("config", GITHUB)]);
+        let action = parse(&url, Path::new("/")).unwrap();
+        let Action::AddToRegistry { name, server } = action else {
+            panic!("expected AddToRegistry, got {:?}", action);
+        };
+        assert_eq!(name, "github");
+        assert_eq!(server.command.as_deref(), Some("npx"));
+    }
+
+    #[test]
+    fn registry_add_rejects_invalid_config_and_names() {
+        let home = Path::new("/");
+        let invalid = error(&link("registry/add", &[("name", "github"), ("config", "{not json")]), home);
+        assert!(invalid.contains("Invalid MCP config"), "{}", invalid);
+        let empty = error(&link("registry/add", &[("name", "github"), ("config", "{}")]), home);
+        assert!(empty.contains("needs a \"command\" or a \"url\""), "{}", empty);
+        let name = error(&link("registry/add", &[("name", ""), ("config", GITHUB)]), home);
+        assert!(name.contains("MCP name is required"), "{}", name);
+    }
+
+    #[test]
+    fn unknown_routes_and_schemes_are_rejected() {
+        let home = Path::new("/");
+        let route = error(&link("registry/remove", &[("name", "github")]), home);
+        assert_eq!(route, "Unknown link \"registry/remove\"");
+        assert!(error("https://open?project=/tmp", home).contains("Not a claude-config:// link"));
+        assert!(error("not a link", home).starts_with("Invalid link"));
+    }
+
+    #[test]
+    fn parameters_must_be_given_exactly_once() {
+        let home = Path::new("/");
+        let twice = error(&link("open", &[("project", "/a"), ("project", "/b")]), home);
+        assert_eq!(twice, "Parameter \"project\" given twice");
+        let unexpected = error(&link("open", &[("project", "/"), ("apply", "true")]), home);
+        assert_eq!(unexpected, "Unexpected parameter \"apply\"");
+        let missing = error(&link("registry/add", &[("name", "github")]), home);
+        assert_eq!(missing, "Missing parameter \"config\"");
+    }
+
+    #[test]
+    fn over_long_links_are_rejected_before_parsing() {
+        let padding = "x".repeat(MAX_URL_LEN);
+        let url = link("registry/add", &[("name", "github"), ("config", &padding)]);
+        assert!(error(&url, Path::new("/")).contains("longer than"));
+    }
+}
@@ -0,0 +1,78 @@
+//! Line diff for previewing generated files
+
+use similar::TextDiff;
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Unified diff of two texts, empty when they are identical
+///
+/// Only the hunks are returned; the preview names the file itself.
+pub fn unified_diff(old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(range: std::ops::RangeInclusive<usize>) -> String {
+        range.map(|i| format!("line {}\n", i)).collect()
+    }
+
+    #[test]
+    fn identical_texts_have_no_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n"), "");
+        assert_eq!(unified_diff("", ""), "");
+    }
+
+    #[test]
+    fn new_file_is_one_insertion_hunk() {
+        assert_eq!(unified_diff("", "{\n}\n"), "@@ -0,0 +1,2 @@\n+{\n+}\n");
+    }
+
+    #[test]
+    fn emptied_file_is_one_deletion_hunk() {
+        assert_eq!(unified_diff("a\nb\n", ""), "@@ -1,2 +0,0 @@\n-a\n-b\n");
+    }
+
+    #[test]
+    fn change_is_shown_with_three_lines_of_context() {
+        let old = lines(1..=10);
+        let new = old.replace("line 5\n", "line five\n");
+        assert_eq!(
+            unified_diff(&old, &new),
+            "@@ -2,7 +2,7 @@\n line 2\n line 3\n line 4\n-line 5\n+line five\n line 6\n line 7\n line 8\n"
+        );
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let old = lines(1..=30);
+        let new = old.replace("line 3\n", "line three\n").replace("line 25\n", "");
+        let diff = unified_diff(&old, &new);
+        let hunks: Vec<&str> = diff.lines().filter(|l| l.starts_with("@@")).collect();
+        assert_eq!(hunks, ["@@ -1,6 +1,6 @@", "@@ -22,7 +22,6 @@"]);
+    }
+
+    #[test]
+    fn nearby_changes_share_a_hunk() {
+        let old = lines(1..=20);
+        let new = old.replace("line 5\n", "").replace("line 10\n", "line ten\n");
+        let diff = unified_diff(&old, &new);
+        assert_eq!(diff.lines().filter(|l| l.starts_with("@@")).count(), 1);
+        assert!(diff.starts_with("@@ -2,12 +2,11 @@\n"));
+    }
+
+    #[test]
+    fn change_deep_in_a_large_file_is_located() {
+        let old = lines(1..=20_000);
+        let new = old.replace("line 10000\n", "line 10000 changed\n");
+        let diff = unified_diff(&old, &new);
+        assert!(diff.starts_with("@@ -9997,7 +9997,7 @@\n"));
+        assert!(diff.contains("-line 10000\n+line 10000 changed\n"));
+    }
+}
@@ -0,0 +1,91 @@
+//! Where the server accepts HTTP requests
+//!
+//! With a window the server listens on a private socket that only the
+//! `app://` proxy talks to; headless it listens on a loopback port for
+//! browsers.
+
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// `ERROR_PIPE_BUSY`: every instance of the named pipe has a client
+#[cfg(windows)]
+const ERROR_PIPE_BUSY: i32 = 231;
+
+/// How long to keep retrying a busy named pipe, and how often
+#[cfg(windows)]
+const PIPE_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+#[cfg(windows)]
+const PIPE_BUSY_RETRY: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Tcp(u16),
+    /// Unix domain socket, or named pipe on Windows
+    Socket(PathBuf),
+}
+
+/// Connection to the server
+pub trait Stream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Stream for T {}
+
+impl Endpoint {
+    /// Connect, giving up on reads after `timeout`
+    pub fn connect(&self, timeout: Duration) -> io::Result<Box<dyn Stream>> {
+        match self {
+            Endpoint::Tcp(port) => {
+                let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, *port));
+                let stream = TcpStream::connect_timeout(&addr, timeout)?;
+                stream.set_read_timeout(Some(timeout))?;
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            Endpoint::Socket(path) => {
+                let stream = std::os::unix::net::UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(timeout))?;
+                Ok(Box::new(stream))
+            }
+            // Named pipes open like files, without a read timeout. Each
+            // instance takes one client, so while all are busy wait for the
+            // server to create the next one.
+            #[cfg(windows)]
+            Endpoint::Socket(path) => {
+                let deadline = std::time::Instant::now() + timeout.min(PIPE_BUSY_TIMEOUT);
+                loop {
+                    match std::fs::OpenOptions::new().read(true).write(true).open(path) {
+                        Ok(pipe) => return Ok(Box::new(pipe)),
+                        Err(e)
+                            if e.raw_os_error() == Some(ERROR_PIPE_BUSY)
+                                && std::time::Instant::now() < deadline =>
+                        {
+                            std::thread::sleep(PIPE_BUSY_RETRY);
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+    }
+
+    /// `Host` header for requests
+    pub fn host(&self) -> String {
+        match self {
+            Endpoint::Tcp(port) => format!("localhost:{}", port),
+            Endpoint::Socket(_) => "localhost".to_string(),
+        }
+    }
+}
+
+/// Read until the server closes the connection
+///
+/// A named pipe reports its end as a broken pipe rather than EOF.
+pub fn read_to_end<R: Read + ?Sized>(stream: &mut R) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    match stream.read_to_end(&mut data) {
+        Ok(_) => Ok(data),
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(data),
+        Err(e) => Err(e),
+    }
+}
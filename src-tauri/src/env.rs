@@ -0,0 +1,228 @@
+//! `.env` loading and `${VAR}` interpolation
+//!
+//! Mirrors `loadEnvFile`, `interpolate` and `resolveEnvVars` in
+//! `lib/utils.js`.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+pub type EnvVars = HashMap<String, String>;
+
+/// Where a `${VAR}` missing from the `.env` files is looked up
+pub type Lookup = fn(&str) -> Option<String>;
+
+/// [`Lookup`] in the process environment
+pub fn process_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// What to do with a `${VAR}` that has no value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Leave the reference in place for the tool to expand at launch
+    Keep,
+    /// Substitute an empty string, for tools that cannot expand variables
+    Resolve,
+    /// Fail with [`Error::UnresolvedVariable`]
+    Require,
+}
+
+/// Load `KEY=value` lines from a `.env` file, treating a missing file as empty
+pub fn load_env_file(path: &Path) -> Result<EnvVars> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(EnvVars::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut vars = EnvVars::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            continue;
+        }
+        vars.insert(key.to_string(), unquote(value.trim()).to_string());
+    }
+    Ok(vars)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+/// Replace `${VAR}` in every string of `value`
+///
+/// Variables are looked up in `env`, then with `fallback`, normally
+/// [`process_var`]. Empty values count as unset, as in the Node
+/// implementation.
+pub fn interpolate(value: &Value, env: &EnvVars, fallback: Lookup, mode: Interpolation) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(interpolate_str(s, env, fallback, mode)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| interpolate(item, env, fallback, mode))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), interpolate(v, env, fallback, mode)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Replace `${VAR}` in a single string, see [`interpolate`]
+pub fn interpolate_str(s: &str, env: &EnvVars, fallback: Lookup, mode: Interpolation) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let reference = &rest[start..start + 3 + len];
+        let name = &reference[2..reference.len() - 1];
+
+        out.push_str(&rest[..start]);
+        match lookup(name, env, fallback) {
+            Some(value) => out.push_str(&value),
+            None if name.is_empty() || mode == Interpolation::Keep => out.push_str(reference),
+            None if mode == Interpolation::Resolve => {
+                log::warn!("Environment variable {} not set", name);
+            }
+            None => return Err(Error::UnresolvedVariable(name.to_string())),
+        }
+        rest = &rest[start + 3 + len..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn lookup(name: &str, env: &EnvVars, fallback: Lookup) -> Option<String> {
+    if name.is_empty() {
+        return None;
+    }
+    env.get(name)
+        .filter(|v| !v.is_empty())
+        .cloned()
+        .or_else(|| fallback(name).filter(|v| !v.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn env(pairs: &[(&str, &str)]) -> EnvVars {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    /// A process environment with nothing set
+    fn unset(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn env_file_is_parsed_like_load_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        std::fs::write(
+            &path,
+            "# comment\n\nAPI_KEY=abc\n  SPACED = value  \nQUOTED=\"a b\"\nSINGLE='c'\nURL=http://x?a=b\nnot a pair\n=no key\n",
+        )
+        .unwrap();
+
+        let vars = load_env_file(&path).unwrap();
+        assert_eq!(
+            vars,
+            env(&[
+                ("API_KEY", "abc"),
+                ("SPACED", "value"),
+                ("QUOTED", "a b"),
+                ("SINGLE", "c"),
+                ("URL", "http://x?a=b"),
+            ])
+        );
+    }
+
+    #[test]
+    fn missing_env_file_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_env_file(&dir.path().join(".env")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn keep_replaces_known_and_leaves_unknown_references() {
+        let vars = env(&[("TOKEN", "secret"), ("EMPTY", "")]);
+        let value = json!({
+            "command": "npx",
+            "args": ["--token", "${TOKEN}", "${UNSET}"],
+            "env": { "A": "pre-${TOKEN}-post", "B": "${EMPTY}", "C": "${}" },
+            "port": 8080,
+            "enabled": true,
+        });
+
+        // Same output as `interpolate` in lib/utils.js
+        assert_eq!(
+            interpolate(&value, &vars, unset, Interpolation::Keep).unwrap(),
+            json!({
+                "command": "npx",
+                "args": ["--token", "secret", "${UNSET}"],
+                "env": { "A": "pre-secret-post", "B": "${EMPTY}", "C": "${}" },
+                "port": 8080,
+                "enabled": true,
+            })
+        );
+    }
+
+    #[test]
+    fn unterminated_reference_is_left_alone() {
+        let vars = env(&[("A", "1")]);
+        assert_eq!(interpolate_str("${A} ${A", &vars, unset, Interpolation::Require).unwrap(), "1 ${A");
+    }
+
+    #[test]
+    fn fallback_is_used_for_missing_and_empty_values() {
+        fn process(name: &str) -> Option<String> {
+            (name == "TOKEN").then(|| "from-process".to_string())
+        }
+        let expand = |vars: &EnvVars| interpolate_str("${TOKEN}", vars, process, Interpolation::Keep).unwrap();
+
+        assert_eq!(expand(&env(&[])), "from-process");
+        assert_eq!(expand(&env(&[("TOKEN", "")])), "from-process");
+        assert_eq!(expand(&env(&[("TOKEN", "from-file")])), "from-file");
+    }
+
+    #[test]
+    fn resolve_empties_unknown_references() {
+        assert_eq!(
+            interpolate_str("a${UNSET}b", &env(&[]), unset, Interpolation::Resolve).unwrap(),
+            "ab"
+        );
+    }
+
+    #[test]
+    fn require_fails_on_unknown_references() {
+        let err = interpolate_str("${UNSET}", &env(&[]), unset, Interpolation::Require).unwrap_err();
+        assert!(matches!(err, Error::UnresolvedVariable(name) if name == "UNSET"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Error type returned by native commands
+//!
+//! Errors serialize as their message so the UI receives a plain string, like
+//! the `{ error }` bodies of the HTTP routes.
+
+use std::path::PathBuf;
+
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+
+    #[error(transparent)]
+    Updater(#[from] tauri_plugin_updater::Error),
+
+    #[error("No update manifest at {}", .0.display())]
+    NoManifest(PathBuf),
+
+    #[error("Terminal: {0}")]
+    Terminal(String),
+
+    #[error("Cannot watch config folders: {0}")]
+    Watch(#[from] notify::Error),
+
+    #[error("Invalid JSON in {path}: {source}")]
+    InvalidJson {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid TOML in {path}: {source}")]
+    InvalidToml {
+        path: PathBuf,
+        source: toml_edit::TomlError,
+    },
+
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error("\"{0}\" not found in registry")]
+    NotInRegistry(String),
+
+    #[error("No {file} found in {} or parent directories", .dir.display())]
+    NoConfig { file: String, dir: PathBuf },
+
+    #[error("Unknown tool \"{0}\"")]
+    UnknownTool(String),
+
+    #[error("MCP \"{0}\" is included but not in the registry")]
+    MissingRegistryEntry(String),
+
+    #[error("Environment variable {0} is not set")]
+    UnresolvedVariable(String),
+
+    #[error("No secrets vault at {}", .0.display())]
+    NoVault(PathBuf),
+
+    #[error("The secrets vault is locked")]
+    VaultLocked,
+
+    #[error("Wrong passphrase, or the vault is damaged")]
+    WrongPassphrase,
+
+    #[error("Cannot {operation} {}: {reason}", .path.display())]
+    AccessDenied {
+        operation: &'static str,
+        path: PathBuf,
+        reason: &'static str,
+    },
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,424 @@
+//! File operations on paths chosen by the UI
+//!
+//! The file explorer reads, writes, moves and deletes whatever paths the UI
+//! sends, so the host confines them to a [`Sandbox`]: the projects registered
+//! in `projects.json`, the tool folders of the directories above them (the
+//! config hierarchy), and the tools' own folders in the home directory. Paths
+//! are canonicalised first, so `..` and symlinks cannot lead outside, and
+//! every refused operation is recorded in the audit log.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::error::{Error, Result};
+use crate::logging;
+use crate::projects::ProjectsRegistry;
+use crate::tools;
+use crate::util::write_atomic;
+
+/// Tool folders in the home directory, allowed even outside a project
+const TOOL_DIRS: &[&str] = &[".claude", ".gemini", ".codex"];
+
+/// Who asked for an operation, as recorded in the audit log
+#[derive(Debug, Clone, Copy)]
+pub enum Caller {
+    Webview,
+    Server,
+}
+
+/// How an operation touches its path
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Access {
+    Read,
+    Write,
+    /// Deletes or moves the entry itself; a symlink is not followed
+    Remove,
+}
+
+/// Kind and size of an entry, for existence checks of the file explorer
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStat {
+    pub is_directory: bool,
+    pub size: u64,
+}
+
+/// Folders the UI may operate in
+#[derive(Debug)]
+pub struct Sandbox {
+    /// Registered projects, with everything in them
+    projects: Vec<PathBuf>,
+    /// Tool folders of the projects, their parents and the home directory
+    config_dirs: Vec<PathBuf>,
+    /// Never removed as a whole
+    protected: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// Sandbox for the projects currently in `projects.json`
+    pub fn load(home: &Path) -> Self {
+        let registry = ProjectsRegistry::load(home);
+
+        let projects: Vec<PathBuf> = registry
+            .projects
+            .iter()
+            .filter_map(|project| resolve(&project.path).ok())
+            .collect();
+
+        // Like `findAllConfigs`, a project and every directory above it up
+        // to, but not including, the filesystem root contribute their config;
+        // a project's own tool folders may be symlinks out of it
+        let mut config_dirs: Vec<PathBuf> = TOOL_DIRS
+            .iter()
+            .filter_map(|folder| resolve(&home.join(folder)).ok())
+            .collect();
+        let tool_dirs = config_dirs.clone();
+        for project in &registry.projects {
+            for dir in project.path.ancestors().filter(|d| d.parent().is_some()) {
+                for target in tools::TARGETS {
+                    if let Ok(folder) = resolve(&dir.join(&target.paths().project_folder)) {
+                        if !config_dirs.contains(&folder) {
+                            config_dirs.push(folder);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut protected = projects.clone();
+        protected.extend(tool_dirs);
+        Self {
+            projects,
+            config_dirs,
+            protected,
+        }
+    }
+
+    /// Resolve `path` for `access`, or refuse it
+    fn check(&self, operation: &'static str, path: &Path, access: Access) -> Result<PathBuf> {
+        let denied = |reason| Error::AccessDenied {
+            operation,
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        let resolved = match access {
+            Access::Read | Access::Write => resolve(path),
+            // The entry itself, in its resolved parent
+            Access::Remove => match (path.parent(), path.file_name()) {
+                (Some(parent), Some(name)) => resolve(parent).map(|parent| parent.join(name)),
+                _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "no file name")),
+            },
+        }
+        .map_err(|_| denied("not an absolute path that can be resolved"))?;
+
+        let inside = self
+            .projects
+            .iter()
+            .chain(&self.config_dirs)
+            .any(|root| resolved.starts_with(root));
+        if !inside {
+            return Err(denied("outside registered projects and tool folders"));
+        }
+        if access == Access::Remove && self.protected.contains(&resolved) {
+            return Err(denied("a project or tool folder cannot be removed"));
+        }
+        Ok(resolved)
+    }
+
+    /// `None` if nothing exists at `path`
+    pub fn stat(&self, path: &Path) -> Result<Option<FileStat>> {
+        let path = self.check("stat", path, Access::Read)?;
+        match fs::metadata(path) {
+            Ok(metadata) => Ok(Some(FileStat {
+                is_directory: metadata.is_dir(),
+                size: metadata.len(),
+            })),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn read(&self, path: &Path) -> Result<String> {
+        let path = self.check("read", path, Access::Read)?;
+        if path.is_dir() {
+            return Err(Error::Invalid(format!("Path is a directory: {}", path.display())));
+        }
+        Ok(fs::read_to_string(path)?)
+    }
+
+    /// Write `content`, creating missing parent folders
+    pub fn write(&self, path: &Path, content: &str) -> Result<()> {
+        let path = self.check("write", path, Access::Write)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&path, content)
+    }
+
+    /// Delete a file, or a folder with everything in it
+    pub fn delete(&self, path: &Path) -> Result<()> {
+        let path = self.check("delete", path, Access::Remove)?;
+        if fs::symlink_metadata(&path)?.is_dir() {
+            fs::remove_dir_all(path)?;
+        } else {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Copy a file, replacing `target` if it exists
+    pub fn copy(&self, source: &Path, target: &Path) -> Result<()> {
+        let source = self.check("copy", source, Access::Read)?;
+        let target = self.check("copy to", target, Access::Write)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, target)?;
+        Ok(())
+    }
+
+    /// Move a file or folder to a `target` that does not exist yet
+    pub fn rename(&self, source: &Path, target: &Path) -> Result<()> {
+        let source = self.check("move", source, Access::Remove)?;
+        let target = self.check("move to", target, Access::Write)?;
+        if target.exists() {
+            return Err(Error::Invalid(format!("{} already exists", target.display())));
+        }
+        fs::rename(source, target)?;
+        Ok(())
+    }
+
+    pub fn create_dir(&self, path: &Path) -> Result<()> {
+        let path = self.check("create", path, Access::Write)?;
+        fs::create_dir_all(path)?;
+        Ok(())
+    }
+}
+
+/// Canonicalise `path`, which may not exist yet
+///
+/// The nearest existing ancestor is resolved and the missing components are
+/// appended, so they cannot contain `..` or a dangling symlink.
+fn resolve(path: &Path) -> io::Result<PathBuf> {
+    if !path.is_absolute() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "not an absolute path"));
+    }
+
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        match fs::canonicalize(existing) {
+            Ok(resolved) => return Ok(missing.iter().rev().fold(resolved, |dir, name| dir.join(name))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                // A symlink to nowhere would be followed once created
+                if fs::symlink_metadata(existing).is_ok() {
+                    return Err(e);
+                }
+                let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                    return Err(e);
+                };
+                missing.push(name);
+                existing = parent;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Run `operation` in the current sandbox, auditing a refusal
+pub fn sandboxed<T>(
+    app: &tauri::AppHandle,
+    caller: Caller,
+    operation: impl FnOnce(&Sandbox) -> Result<T>,
+) -> Result<T> {
+    let sandbox = Sandbox::load(&app.path().home_dir()?);
+    let result = operation(&sandbox);
+    if let Err(e @ Error::AccessDenied { .. }) = &result {
+        logging::audit(app, &format!("{:?}: {}", caller, e));
+    }
+    result
+}
+
+#[tauri::command]
+pub fn file_read(app: tauri::AppHandle, path: PathBuf) -> Result<String> {
+    sandboxed(&app, Caller::Webview, |sandbox| sandbox.read(&path))
+}
+
+#[tauri::command]
+pub fn file_write(app: tauri::AppHandle, path: PathBuf, content: String) -> Result<()> {
+    sandboxed(&app, Caller::Webview, |sandbox| sandbox.write(&path, &content))
+}
+
+#[tauri::command]
+pub fn file_delete(app: tauri::AppHandle, path: PathBuf) -> Result<()> {
+    sandboxed(&app, Caller::Webview, |sandbox| sandbox.delete(&path))
+}
+
+#[tauri::command]
+pub fn file_copy(app: tauri::AppHandle, source: PathBuf, target: PathBuf) -> Result<()> {
+    sandboxed(&app, Caller::Webview, |sandbox| sandbox.copy(&source, &target))
+}
+
+#[tauri::command]
+pub fn file_rename(app: tauri::AppHandle, source: PathBuf, target: PathBuf) -> Result<()> {
+    sandboxed(&app, Caller::Webview, |sandbox| sandbox.rename(&source, &target))
+}
+
+#[tauri::command]
+pub fn file_create_dir(app: tauri::AppHandle, path: PathBuf) -> Result<()> {
+    sandboxed(&app, Caller::Webview, |sandbox| sandbox.create_dir(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A home with one registered project, `work/app`, a config folder in
+    /// `work` above it, an unregistered `work/other` and an `outside` folder
+    struct Fixture {
+        _tmp: tempfile::TempDir,
+        root: PathBuf,
+        home: PathBuf,
+        project: PathBuf,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let tmp = tempfile::tempdir().unwrap();
+            let root = fs::canonicalize(tmp.path()).unwrap();
+            let home = root.join("home");
+            let project = root.join("work").join("app");
+            for dir in [
+                home.join(".claude"),
+                home.join(".claude-config"),
+                project.join(".claude"),
+                root.join("work").join(".claude"),
+                root.join("work").join("other").join(".claude"),
+                root.join("outside"),
+            ] {
+                fs::create_dir_all(dir).unwrap();
+            }
+            fs::write(project.join("CLAUDE.md"), "# App\n").unwrap();
+            fs::write(root.join("work").join(".claude").join("mcps.json"), "{}").unwrap();
+            fs::write(root.join("work").join("other").join(".claude").join("mcps.json"), "{}").unwrap();
+            fs::write(root.join("outside").join("secret.txt"), "secret").unwrap();
+
+            let projects = serde_json::json!({ "projects": [{ "id": "app", "path": project }] });
+            fs::write(home.join(".claude-config").join("projects.json"), projects.to_string()).unwrap();
+
+            Self {
+                _tmp: tmp,
+                root,
+                home,
+                project,
+            }
+        }
+
+        fn sandbox(&self) -> Sandbox {
+            Sandbox::load(&self.home)
+        }
+    }
+
+    /// The reason an operation was refused, or `None` if it was allowed
+    fn refusal<T>(result: Result<T>) -> Option<&'static str> {
+        match result {
+            Err(Error::AccessDenied { reason, .. }) => Some(reason),
+            _ => None,
+        }
+    }
+
+    const UNRESOLVABLE: Option<&str> = Some("not an absolute path that can be resolved");
+    const OUTSIDE: Option<&str> = Some("outside registered projects and tool folders");
+    const PROTECTED: Option<&str> = Some("a project or tool folder cannot be removed");
+
+    #[test]
+    fn projects_and_tool_folders_are_allowed() {
+        let fixture = Fixture::new();
+        let sandbox = fixture.sandbox();
+
+        assert_eq!(sandbox.read(&fixture.project.join("CLAUDE.md")).unwrap(), "# App\n");
+        assert_eq!(sandbox.read(&fixture.root.join("work/.claude/mcps.json")).unwrap(), "{}");
+        sandbox.write(&fixture.project.join(".claude/rules/new.md"), "rule").unwrap();
+        sandbox.write(&fixture.home.join(".claude/commands/hi.md"), "hi").unwrap();
+        assert!(sandbox.stat(&fixture.project.join("missing.md")).unwrap().is_none());
+        assert!(sandbox.stat(&fixture.project.join(".claude")).unwrap().unwrap().is_directory);
+    }
+
+    #[test]
+    fn relative_paths_are_refused() {
+        let fixture = Fixture::new();
+        let sandbox = fixture.sandbox();
+        assert_eq!(refusal(sandbox.read(Path::new("work/app/CLAUDE.md"))), UNRESOLVABLE);
+        assert_eq!(refusal(sandbox.write(Path::new("CLAUDE.md"), "")), UNRESOLVABLE);
+    }
+
+    #[test]
+    fn parent_components_cannot_leave_the_project() {
+        let fixture = Fixture::new();
+        let sandbox = fixture.sandbox();
+
+        // Existing paths are canonicalised
+        let existing = fixture.project.join("../../outside/secret.txt");
+        assert_eq!(refusal(sandbox.read(&existing)), OUTSIDE);
+        assert_eq!(refusal(sandbox.stat(&existing)), OUTSIDE);
+        // Missing ones may not contain `..` at all
+        let missing = fixture.project.join("new/../../../outside/new.txt");
+        assert_eq!(refusal(sandbox.write(&missing, "x")), UNRESOLVABLE);
+        assert!(!fixture.root.join("outside/new.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_cannot_leave_the_project() {
+        let fixture = Fixture::new();
+        let outside = fixture.root.join("outside");
+        std::os::unix::fs::symlink(&outside, fixture.project.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("new.txt"), fixture.project.join("dangling")).unwrap();
+        let sandbox = fixture.sandbox();
+
+        assert_eq!(refusal(sandbox.read(&fixture.project.join("link/secret.txt"))), OUTSIDE);
+        assert_eq!(refusal(sandbox.write(&fixture.project.join("link/new.txt"), "x")), OUTSIDE);
+        assert_eq!(refusal(sandbox.write(&fixture.project.join("dangling"), "x")), UNRESOLVABLE);
+        assert!(!outside.join("new.txt").exists());
+
+        // Removing the link removes only the link
+        sandbox.delete(&fixture.project.join("link")).unwrap();
+        assert!(outside.join("secret.txt").exists());
+    }
+
+    #[test]
+    fn projects_and_tool_folders_cannot_be_removed() {
+        let fixture = Fixture::new();
+        let sandbox = fixture.sandbox();
+
+        assert_eq!(refusal(sandbox.delete(&fixture.project)), PROTECTED);
+        assert_eq!(refusal(sandbox.delete(&fixture.home.join(".claude"))), PROTECTED);
+        let moved = fixture.project.join(".claude/moved");
+        assert_eq!(refusal(sandbox.rename(&fixture.home.join(".claude"), &moved)), PROTECTED);
+        assert!(fixture.project.is_dir() && fixture.home.join(".claude").is_dir());
+
+        // Their contents can
+        sandbox.delete(&fixture.project.join(".claude")).unwrap();
+        sandbox.delete(&fixture.project.join("CLAUDE.md")).unwrap();
+    }
+
+    #[test]
+    fn unregistered_projects_and_other_folders_are_refused() {
+        let fixture = Fixture::new();
+        let sandbox = fixture.sandbox();
+        let other = fixture.root.join("work/other");
+
+        assert_eq!(refusal(sandbox.read(&other.join(".claude/mcps.json"))), OUTSIDE);
+        assert_eq!(refusal(sandbox.create_dir(&other.join(".claude/rules"))), OUTSIDE);
+        assert_eq!(refusal(sandbox.read(&fixture.root.join("outside/secret.txt"))), OUTSIDE);
+        assert_eq!(refusal(sandbox.write(&fixture.home.join(".bashrc"), "")), OUTSIDE);
+        let copied = fixture.root.join("outside/copy.md");
+        assert_eq!(refusal(sandbox.copy(&fixture.project.join("CLAUDE.md"), &copied)), OUTSIDE);
+        assert!(!copied.exists());
+    }
+}
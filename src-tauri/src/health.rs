@@ -0,0 +1,54 @@
+//! HTTP health check and version handshake against the UI server
+//!
+//! Requests carry the launch token, as the server rejects anything without it.
+
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::endpoint::{self, Endpoint, Stream};
+
+/// Endpoint that answers as soon as the server is listening
+const HEALTH_PATH: &str = "/api/version";
+
+/// Header the server accepts the token in, besides its cookie
+pub const TOKEN_HEADER: &str = "X-Claude-Config-Token";
+
+/// Connect and read timeout for a single probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Send `GET /api/version` to the server at `endpoint`
+fn request_version(endpoint: &Endpoint, token: &str) -> Option<Box<dyn Stream>> {
+    let mut stream = endpoint.connect(PROBE_TIMEOUT).ok()?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\n{}: {}\r\nConnection: close\r\n\r\n",
+        HEALTH_PATH,
+        endpoint.host(),
+        TOKEN_HEADER,
+        token
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+    Some(stream)
+}
+
+/// Check whether the server at `endpoint` answers `/api/version` with 200
+pub fn server_ready(endpoint: &Endpoint, token: &str) -> bool {
+    let Some(mut stream) = request_version(endpoint, token) else {
+        return false;
+    };
+
+    // Status line starts with "HTTP/1.1 200"
+    let mut status = [0u8; 12];
+    stream.read_exact(&mut status).is_ok() && &status[9..] == b"200"
+}
+
+/// Version the server at `endpoint` reports, read from its package.json
+pub fn server_version(endpoint: &Endpoint, token: &str) -> Option<String> {
+    let response = endpoint::read_to_end(request_version(endpoint, token)?.as_mut()).ok()?;
+    let response = String::from_utf8_lossy(&response);
+
+    // The body may be sent chunked, so take the JSON object itself
+    let body = &response[response.find('{')?..=response.rfind('}')?];
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    value.get("version")?.as_str().map(str::to_string)
+}
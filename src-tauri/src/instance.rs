@@ -0,0 +1,81 @@
+//! Single instance
+//!
+//! A second launch would start a second server fighting over the same port,
+//! so it is handed to the running instance instead: the window comes to the
+//! front, and a project directory among the arguments becomes the open
+//! project, as with `/api/switch-project`. `--profile` switches the
+//! workstream, `--headless` leaves the window where it is, and on Windows
+//! and Linux a `claude-config://` link arrives this way too.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::cli::{self, LaunchArgs};
+use crate::deep_link;
+use crate::watcher::ConfigWatcher;
+
+/// Event asking the webview to switch to another project
+pub const OPEN_PROJECT: &str = "open-project";
+
+/// Payload of [`OPEN_PROJECT`]
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenProject {
+    pub dir: PathBuf,
+}
+
+/// Called in the running instance with the arguments of a second launch
+pub fn on_second_instance(app: &tauri::AppHandle, args: Vec<String>, cwd: String) {
+    log::info!("Launched again with {:?}", args);
+    let args = match LaunchArgs::parse(args.get(1..).unwrap_or_default(), Path::new(&cwd)) {
+        Ok(args) => args,
+        Err(e) => {
+            log::warn!("Ignoring arguments of second launch: {}", e);
+            LaunchArgs::default()
+        }
+    };
+    if args.port.is_some() {
+        log::warn!("Ignoring --port, the server is already running");
+    }
+
+    if !args.headless {
+        focus_main_window(app);
+    }
+    if let Some(profile) = &args.profile {
+        cli::use_profile(app, profile);
+    }
+    if let Some(dir) = args.dir {
+        open_project(app, dir);
+    }
+    if let Some(url) = args.url {
+        deep_link::open_url(app, url);
+    }
+}
+
+/// Show the main window and bring it to the front
+pub fn focus_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Watch `dir` and ask the webview to switch to it
+pub fn open_project(app: &tauri::AppHandle, dir: PathBuf) {
+    log::info!("Opening project {}", dir.display());
+
+    match app.path().home_dir() {
+        Ok(home) => {
+            if let Err(e) = app.state::<ConfigWatcher>().watch(app, dir.clone(), home) {
+                log::warn!("Failed to watch {}: {}", dir.display(), e);
+            }
+        }
+        Err(e) => log::warn!("{}", e),
+    }
+
+    if let Err(e) = app.emit(OPEN_PROJECT, OpenProject { dir }) {
+        log::warn!("Failed to emit {}: {}", OPEN_PROJECT, e);
+    }
+}
@@ -0,0 +1,206 @@
+//! Processes started on behalf of the UI
+//!
+//! The webview has no shell permissions, and inside the desktop app the
+//! server does not spawn these itself but asks the host over the bridge.
+//! Each launch is narrow: `claude /init` in an existing directory, and MCP
+//! tool discovery for servers already in the registry, looked up by name.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::Manager;
+
+use crate::env::{self, EnvVars, Interpolation};
+use crate::error::{Error, Result};
+use crate::registry::{self, Registry};
+
+/// Time `claude /init` may take, as in `ui/routes/projects.js`
+const CLAUDE_INIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time an MCP server may take to list its tools
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Protocol version sent in `initialize`, as in `ui/routes/mcp-discovery.js`
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// A tool an MCP server offers
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTool {
+    pub name: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_schema: Option<Value>,
+}
+
+/// Run `claude /init` to write a `CLAUDE.md` for the project in `dir`
+pub fn claude_init(dir: &Path) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(Error::Invalid(format!("Not a directory: {}", dir.display())));
+    }
+
+    log::info!("Running claude /init in {}", dir.display());
+    let mut child = Command::new("claude")
+        .arg("/init")
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    let deadline = Instant::now() + CLAUDE_INIT_TIMEOUT;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            if status.success() {
+                return Ok(());
+            }
+            return Err(Error::Invalid(format!("claude /init failed ({})", status)));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            return Err(Error::Invalid(format!(
+                "claude /init did not finish within {}s",
+                CLAUDE_INIT_TIMEOUT.as_secs()
+            )));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Ask the registry entry `name` for its tools
+///
+/// Only stdio servers are started; others report no tools, like the server's
+/// own discovery.
+pub fn mcp_tools(registry_path: &Path, home: &Path, name: &str) -> Result<Vec<McpTool>> {
+    let server = Registry::load(registry_path)?
+        .mcp_servers
+        .shift_remove(name)
+        .ok_or_else(|| Error::NotInRegistry(name.to_string()))?;
+    let Some(program) = server.command.as_deref() else {
+        return Ok(Vec::new());
+    };
+
+    // `${VAR}` in the command, args and env, from its own env first
+    let server_env = server.env_vars();
+    let vars: EnvVars = server_env.clone().into_iter().collect();
+    let expand = |s: &str| env::interpolate_str(s, &vars, env::process_var, Interpolation::Keep);
+
+    // `npx` and friends are batch files on Windows
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(expand(program)?);
+        command
+    } else {
+        Command::new(expand(program)?)
+    };
+    for arg in &server.arg_strings() {
+        command.arg(expand(arg)?);
+    }
+    for (key, value) in &server_env {
+        command.env(key, expand(value)?);
+    }
+    let cwd = server.extra.get("cwd").and_then(Value::as_str);
+    command
+        .current_dir(cwd.map_or_else(|| home.to_path_buf(), |cwd| home.join(cwd)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    log::info!("Listing tools of MCP \"{}\"", name);
+    let mut child = command
+        .spawn()
+        .map_err(|e| Error::Invalid(format!("Failed to spawn server: {}", e)))?;
+    let result = list_tools(&mut child);
+    let _ = child.kill();
+    let _ = child.wait();
+    result
+}
+
+/// `initialize`, then `tools/list`, over the child's stdio
+fn list_tools(child: &mut Child) -> Result<Vec<McpTool>> {
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let stdout = child.stdout.take().expect("stdout is piped");
+
+    // Responses are JSON-RPC lines, among whatever else the server logs
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(std::io::Result::ok) {
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if message.get("jsonrpc").and_then(Value::as_str) == Some("2.0") && tx.send(message).is_err() {
+                return;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + DISCOVERY_TIMEOUT;
+    let mut request = |id: u64, method: &str, params: Value| -> Result<Value> {
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        writeln!(stdin, "{}", message)?;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match rx.recv_timeout(timeout) {
+                Ok(response) if response.get("id").and_then(Value::as_u64) == Some(id) => {
+                    return Ok(response);
+                }
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(Error::Invalid("Timeout waiting for server response".to_string()));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Invalid("Server exited before listing its tools".to_string()));
+                }
+            }
+        }
+    };
+
+    request(
+        1,
+        "initialize",
+        json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "claude-config-ui", "version": "1.0.0" },
+        }),
+    )?;
+    let response = request(2, "tools/list", json!({}))?;
+
+    let tools = response
+        .pointer("/result/tools")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    Ok(tools
+        .iter()
+        .filter_map(|tool| {
+            Some(McpTool {
+                name: tool.get("name")?.as_str()?.to_string(),
+                description: tool
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                input_schema: tool.get("inputSchema").cloned(),
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn claude_init_project(dir: String) -> Result<()> {
+    let dir = std::path::absolute(dir)?;
+    tauri::async_runtime::spawn_blocking(move || claude_init(&dir)).await?
+}
+
+#[tauri::command]
+pub async fn mcp_server_tools(app: tauri::AppHandle, name: String) -> Result<Vec<McpTool>> {
+    let registry_path = registry::registry_path(&app)?;
+    let home = app.path().home_dir()?;
+    tauri::async_runtime::spawn_blocking(move || mcp_tools(&registry_path, &home, &name)).await?
+}
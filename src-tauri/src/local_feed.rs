@@ -0,0 +1,278 @@
+//! Updates from a local folder
+//!
+//! Machines without internet access cannot reach the release feed, so a
+//! `latest.json` manifest and the update package it names can be copied to a
+//! folder instead. While an update from it is checked and downloaded, the
+//! folder is served on a loopback port with every download URL in the
+//! manifest pointed at the local copy of its file. The regular updater flow
+//! then verifies the signature with the configured public key and installs
+//! it. Release builds only reach that loopback address over plain HTTP with
+//! `dangerousInsecureTransportProtocol`, which would apply to every updater
+//! endpoint, so it is left out of `tauri.conf.json` and merged in from
+//! `tauri.offline.conf.json` for builds meant for machines without internet
+//! access (`tauri build --config tauri.offline.conf.json`).
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::util::load_json;
+
+/// Manifest name looked up when the source is a folder
+const MANIFEST: &str = "latest.json";
+
+/// A client that stops sending or reading is dropped after this long
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest request line and headers read from a client
+const MAX_REQUEST_HEAD: u64 = 16 * 1024;
+
+/// Whether this build may fetch updates over plain HTTP from a local feed
+pub fn is_supported(app: &tauri::AppHandle) -> bool {
+    cfg!(debug_assertions)
+        || app
+            .config()
+            .plugins
+            .0
+            .get("updater")
+            .and_then(|updater| updater.get("dangerousInsecureTransportProtocol"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+}
+
+/// A local update source served until dropped
+pub struct LocalFeed {
+    port: u16,
+    stop: Arc<AtomicBool>,
+}
+
+impl LocalFeed {
+    /// Serve `source`: a folder holding `latest.json`, a manifest file, or a
+    /// `file://` URL of either
+    pub fn start(source: &str) -> Result<Self> {
+        let manifest_path = manifest_path(source)?;
+        let dir = manifest_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let mut manifest: Value =
+            load_json(&manifest_path)?.ok_or_else(|| Error::NoManifest(manifest_path.clone()))?;
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let port = listener.local_addr()?.port();
+        let files = point_urls_at(&mut manifest, &dir, port);
+        let manifest = serde_json::to_vec(&manifest).map_err(|source| Error::InvalidJson {
+            path: manifest_path.clone(),
+            source,
+        })?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let served = Arc::new(Served { manifest, files });
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                // A stalled client must not hold up the updater's requests
+                let served = served.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = stream.and_then(|stream| served.respond(stream)) {
+                        log::warn!("Local update feed: {}", e);
+                    }
+                });
+            }
+        });
+
+        log::info!("Serving update from {} on port {}", manifest_path.display(), port);
+        Ok(Self { port, stop })
+    }
+
+    /// Manifest URL for the updater
+    pub fn endpoint(&self) -> tauri::Url {
+        let url = format!("http://{}:{}/{}", Ipv4Addr::LOCALHOST, self.port, MANIFEST);
+        tauri::Url::parse(&url).expect("valid loopback URL")
+    }
+}
+
+impl Drop for LocalFeed {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake up the blocked accept so the thread sees the flag
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, self.port));
+    }
+}
+
+fn manifest_path(source: &str) -> Result<PathBuf> {
+    let path = match source.strip_prefix("file://") {
+        Some(_) => tauri::Url::parse(source)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| Error::Invalid(format!("Invalid file URL: {}", source)))?,
+        None => PathBuf::from(source),
+    };
+    Ok(if path.is_dir() { path.join(MANIFEST) } else { path })
+}
+
+/// Rewrite the download URLs of a static (`platforms`) or dynamic manifest
+/// to the loopback server, returning the files to serve by URL path
+fn point_urls_at(manifest: &mut Value, dir: &Path, port: u16) -> HashMap<String, PathBuf> {
+    let mut files = HashMap::new();
+    let mut rewrite = |entry: &mut Value| {
+        let Some(url) = entry.get_mut("url") else {
+            return;
+        };
+        let Some(name) = url.as_str().and_then(|u| u.rsplit('/').next()).map(str::to_string) else {
+            return;
+        };
+        *url = Value::String(format!("http://{}:{}/{}", Ipv4Addr::LOCALHOST, port, name));
+        files.insert(name.clone(), dir.join(percent_decode(&name)));
+    };
+
+    if let Some(platforms) = manifest.get_mut("platforms").and_then(Value::as_object_mut) {
+        platforms.values_mut().for_each(&mut rewrite);
+    }
+    rewrite(manifest);
+    files
+}
+
+/// Decode `%XX` escapes in a URL path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(byte) if bytes[i] == b'%' => {
+                decoded.push(byte);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// What the loopback server answers with
+struct Served {
+    manifest: Vec<u8>,
+    files: HashMap<String, PathBuf>,
+}
+
+impl Served {
+    fn respond(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?.take(MAX_REQUEST_HEAD));
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Skip the headers
+        let mut header = String::new();
+        loop {
+            header.clear();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+        }
+
+        let path = request_line
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or("/")
+            .trim_start_matches('/');
+
+        if path == MANIFEST {
+            write_head(&mut stream, "200 OK", "application/json", self.manifest.len() as u64)?;
+            return stream.write_all(&self.manifest);
+        }
+
+        // Only files named in the manifest are served
+        match self.files.get(path).map(std::fs::File::open) {
+            Some(Ok(mut file)) => {
+                let len = file.metadata()?.len();
+                write_head(&mut stream, "200 OK", "application/octet-stream", len)?;
+                std::io::copy(&mut file, &mut stream).map(|_| ())
+            }
+            Some(Err(e)) => {
+                log::warn!("Cannot read update package {}: {}", path, e);
+                write_head(&mut stream, "404 Not Found", "text/plain", 0)
+            }
+            None => write_head(&mut stream, "404 Not Found", "text/plain", 0),
+        }
+    }
+}
+
+fn write_head(stream: &mut TcpStream, status: &str, content_type: &str, len: u64) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, content_type, len
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A folder with a manifest and the package it names
+    fn source() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = serde_json::json!({
+            "version": "9.9.9",
+            "platforms": {
+                "linux-x86_64": {
+                    "signature": "sig",
+                    "url": "https://github.com/regression-io/claude-config/releases/download/v9.9.9/Claude%20Config.AppImage"
+                }
+            }
+        });
+        std::fs::write(dir.path().join(MANIFEST), manifest.to_string()).unwrap();
+        std::fs::write(dir.path().join("Claude Config.AppImage"), b"package").unwrap();
+        dir
+    }
+
+    fn get(port: u16, path: &str) -> String {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        write!(stream, "GET /{} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn manifest_urls_point_at_the_feed() {
+        let dir = source();
+        let feed = LocalFeed::start(&dir.path().to_string_lossy()).unwrap();
+
+        let manifest = get(feed.port, MANIFEST);
+        let expected = format!("http://127.0.0.1:{}/Claude%20Config.AppImage", feed.port);
+        assert!(manifest.starts_with("HTTP/1.1 200 OK"));
+        assert!(manifest.contains(&expected), "{}", manifest);
+        assert!(get(feed.port, "Claude%20Config.AppImage").ends_with("\r\n\r\npackage"));
+        assert!(get(feed.port, "../secret").starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn a_stalled_client_does_not_block_others() {
+        let dir = source();
+        let feed = LocalFeed::start(&dir.path().to_string_lossy()).unwrap();
+
+        // Connects but never sends its request
+        let _stalled = TcpStream::connect((Ipv4Addr::LOCALHOST, feed.port)).unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let port = feed.port;
+        std::thread::spawn(move || tx.send(get(port, MANIFEST)));
+        let response = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("answered while another client stalls");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+}
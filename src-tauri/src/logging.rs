@@ -0,0 +1,168 @@
+//! Log files for the desktop host and the server sidecar
+//!
+//! Release builds have no console (see `windows_subsystem` in `main.rs`), so
+//! everything goes to size-rotated files in the app log directory. Server
+//! output is logged under the `server` target. Denied file operations also
+//! go to a separate audit log next to it.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use log::{LevelFilter, Log, Metadata, Record};
+use tauri::Manager;
+
+/// Name of the active log file
+const LOG_FILE_NAME: &str = "claude-config.log";
+
+/// Name of the audit log of denied file operations
+const AUDIT_FILE_NAME: &str = "audit.log";
+
+/// Size at which the active log file is rotated
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the active one (`.1` is the newest)
+const MAX_ROTATED_FILES: usize = 3;
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size + line.len() as u64 > MAX_LOG_SIZE {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+
+        for i in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, i);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, i + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+struct FileLogger {
+    level: LevelFilter,
+    file: Mutex<LogFile>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        // Keep console output for `cargo tauri dev`
+        if cfg!(debug_assertions) {
+            eprint!("{}", line);
+        }
+
+        if let Err(e) = self.file.lock().unwrap().write_line(&line) {
+            eprintln!("Failed to write log: {}", e);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = self.file.lock().unwrap().file.flush();
+    }
+}
+
+/// Start writing logs to the app log directory
+pub fn init(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = app.path().app_log_dir()?;
+    fs::create_dir_all(&dir)?;
+
+    let level = if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+
+    let logger = FileLogger {
+        level,
+        file: Mutex::new(LogFile::open(dir.join(LOG_FILE_NAME))?),
+    };
+    log::set_boxed_logger(Box::new(logger))?;
+    log::set_max_level(level);
+
+    log::info!(
+        "{} {} starting, logging to {}",
+        app.package_info().name,
+        app.package_info().version,
+        dir.display()
+    );
+    Ok(())
+}
+
+/// Serializes writes to the audit log, which is opened per entry
+static AUDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Record an operation the host refused in the audit log
+pub fn audit(app: &tauri::AppHandle, entry: &str) {
+    log::warn!(target: "audit", "{}", entry);
+
+    let write = || -> Result<(), Box<dyn std::error::Error>> {
+        let dir = app.path().app_log_dir()?;
+        fs::create_dir_all(&dir)?;
+        let _lock = AUDIT_LOCK.lock().unwrap();
+        let line = format!("{} {}\n", chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"), entry);
+        LogFile::open(dir.join(AUDIT_FILE_NAME))?.write_line(&line)?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        log::error!("Failed to write audit log: {}", e);
+    }
+}
+
+/// Open the log directory in the system file manager
+pub fn open_log_dir(app: &tauri::AppHandle) -> crate::error::Result<()> {
+    let dir = app.path().app_log_dir()?;
+    fs::create_dir_all(&dir)?;
+    open::that(&dir)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn open_logs(app: tauri::AppHandle) -> crate::error::Result<()> {
+    open_log_dir(&app)
+}
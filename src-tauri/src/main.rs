@@ -1,193 +1,170 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod apply;
+mod auto_apply;
+mod bridge;
+mod cli;
+mod config;
+mod deep_link;
+mod diff;
+mod endpoint;
+mod env;
+mod error;
+mod files;
+mod health;
+mod instance;
+mod launch;
+mod local_feed;
+mod logging;
+mod preferences;
+mod projects;
+mod proxy;
+mod registry;
+mod release_notes;
+mod rollback;
+mod sidecar;
+mod terminal;
+mod tools;
+mod tray;
+mod updater;
+mod util;
+mod vault;
+mod watcher;
+mod workstreams;
+
 use tauri::Manager;
-use tauri_plugin_shell::ShellExt;
-use tauri_plugin_shell::process::CommandEvent;
-use tauri_plugin_updater::UpdaterExt;
-use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use cli::LaunchArgs;
+use release_notes::ReleaseNotesState;
+use sidecar::ServerSupervisor;
+use terminal::TerminalState;
+use updater::UpdateState;
+use vault::VaultState;
+use watcher::ConfigWatcher;
 
 fn main() {
+    let args = match LaunchArgs::from_env() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if let Some(command) = &args.secrets {
+        if let Err(e) = vault::run_cli(command) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tauri::Builder::default()
+        // Must come first, so a second launch exits before starting anything
+        .plugin(tauri_plugin_single_instance::init(instance::on_second_instance))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .register_asynchronous_uri_scheme_protocol(proxy::SCHEME, |ctx, request, responder| {
+            proxy::handle(ctx.app_handle(), request, responder)
+        })
+        .manage(args)
+        .manage(ServerSupervisor::default())
+        .manage(ConfigWatcher::default())
+        .manage(UpdateState::default())
+        .manage(ReleaseNotesState::default())
+        .manage(VaultState::default())
+        .manage(TerminalState::default())
+        .invoke_handler(tauri::generate_handler![
+            apply::apply_config,
+            apply::apply_tools,
+            config::effective_config,
+            files::file_read,
+            files::file_write,
+            files::file_delete,
+            files::file_copy,
+            files::file_rename,
+            files::file_create_dir,
+            launch::claude_init_project,
+            launch::mcp_server_tools,
+            logging::open_logs,
+            registry::registry_list,
+            registry::registry_add,
+            registry::registry_remove,
+            release_notes::answer_update,
+            release_notes::release_notes,
+            rollback::rollback_supported,
+            terminal::terminal_open,
+            terminal::terminal_write,
+            terminal::terminal_resize,
+            terminal::terminal_close,
+            updater::cancel_update,
+            updater::check_for_updates,
+            updater::update_settings,
+            updater::set_update_settings,
+            updater::update_from_file,
+            vault::vault_status,
+            vault::vault_unlock,
+            vault::vault_lock,
+            vault::vault_list,
+            vault::vault_set,
+            vault::vault_remove,
+            vault::vault_rotate,
+            watcher::watch_project,
+        ])
         .setup(|app| {
             let app_handle = app.handle().clone();
 
-            // Spawn server startup in background
+            if let Err(e) = logging::init(&app_handle) {
+                eprintln!("Failed to initialize logging: {}", e);
+            }
+
+            // Before the server starts, so a broken update is caught even if
+            // it never gets that far
+            rollback::on_launch(&app_handle);
+
+            if let Some(profile) = &app_handle.state::<LaunchArgs>().profile {
+                cli::use_profile(&app_handle, profile);
+            }
+
+            if let Err(e) = watcher::watch_active_project(&app_handle) {
+                log::warn!("Failed to watch the active project: {}", e);
+            }
+
+            if let Err(e) = tray::init(&app_handle) {
+                log::error!("Failed to create tray icon: {}", e);
+            }
+
+            deep_link::init(&app_handle);
+
+            // Run the server under supervision in background
             let server_handle = app_handle.clone();
             std::thread::spawn(move || {
-                if let Err(e) = start_server(&server_handle) {
-                    eprintln!("Failed to start server: {}", e);
-                }
+                deep_link::handle_startup(&server_handle);
+                sidecar::supervise(&server_handle);
             });
 
             // Check for updates in background
             let update_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                check_for_updates(update_handle).await;
+                updater::run_periodic_checks(update_handle).await;
             });
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
-
-async fn check_for_updates(app: tauri::AppHandle) {
-    // Wait a few seconds for the app to fully load
-    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-
-    match app.updater().check().await {
-        Ok(Some(update)) => {
-            let version = update.version.clone();
-            let body = update.body.clone().unwrap_or_default();
-
-            // Ask user if they want to update
-            let should_update = app.dialog()
-                .message(format!(
-                    "A new version ({}) is available!\n\n{}\n\nWould you like to download and install it?",
-                    version,
-                    body.chars().take(200).collect::<String>()
-                ))
-                .kind(MessageDialogKind::Info)
-                .title("Update Available")
-                .ok_button_label("Update")
-                .cancel_button_label("Later")
-                .blocking_show();
-
-            if should_update {
-                println!("User accepted update to {}", version);
-
-                // Download and install the update
-                match update.download_and_install(|_, _| {}, || {}).await {
-                    Ok(_) => {
-                        app.dialog()
-                            .message("Update installed! The app will now restart.")
-                            .kind(MessageDialogKind::Info)
-                            .title("Update Complete")
-                            .blocking_show();
-
-                        // Restart the app
-                        app.restart();
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to install update: {}", e);
-                        app.dialog()
-                            .message(format!("Failed to install update: {}\n\nPlease download manually from GitHub.", e))
-                            .kind(MessageDialogKind::Error)
-                            .title("Update Failed")
-                            .blocking_show();
-                    }
-                }
-            }
-        }
-        Ok(None) => {
-            println!("No updates available");
-        }
-        Err(e) => {
-            eprintln!("Failed to check for updates: {}", e);
-        }
-    }
-}
-
-fn start_server(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    // Get the resource directory where server files are bundled
-    let resource_dir = app.path().resource_dir()?;
-    let server_dir = resource_dir.join("server");
-
-    // Check if we're in production (bundled) or development mode
-    if server_dir.exists() {
-        // Production: use bundled sidecar (Node.js) and server script
-        start_production_server(app, &server_dir)
-    } else {
-        // Development: use system node and local cli.js
-        start_development_server(app)
-    }
-}
-
-fn start_production_server(app: &tauri::AppHandle, server_dir: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
-    let sidecar = app.shell().sidecar("node-server")?;
-    let cli_path = server_dir.join("cli.js");
-
-    // Spawn the sidecar (Node.js) with the cli.js script as first argument
-    let (mut rx, _child) = sidecar
-        .args([
-            cli_path.to_string_lossy().to_string(),
-            "ui".to_string(),
-            "--foreground".to_string(),
-            "--port".to_string(),
-            "3333".to_string(),
-        ])
-        .env("NODE_PATH", server_dir.join("node_modules").to_string_lossy().to_string())
-        .spawn()?;
-
-    // Log output in background
-    std::thread::spawn(move || {
-        while let Some(event) = rx.blocking_recv() {
-            handle_command_event(event);
-        }
-    });
-
-    Ok(())
-}
-
-fn start_development_server(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
-    let cli_path = find_dev_cli_path();
-
-    let shell = app.shell();
-    let (mut rx, _child) = shell
-        .command("node")
-        .args([&cli_path, "ui", "--foreground", "--port", "3333"])
-        .spawn()?;
-
-    // Log output in background
-    std::thread::spawn(move || {
-        while let Some(event) = rx.blocking_recv() {
-            handle_command_event(event);
-        }
-    });
-
-    Ok(())
-}
-
-fn handle_command_event(event: CommandEvent) {
-    match event {
-        CommandEvent::Stdout(line) => {
-            if let Ok(s) = String::from_utf8(line) {
-                println!("[server] {}", s);
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<ServerSupervisor>().shutdown();
+                app.state::<TerminalState>().close_all();
+                updater::install_pending(app);
             }
-        }
-        CommandEvent::Stderr(line) => {
-            if let Ok(s) = String::from_utf8(line) {
-                eprintln!("[server] {}", s);
-            }
-        }
-        CommandEvent::Error(e) => {
-            eprintln!("[server error] {}", e);
-        }
-        CommandEvent::Terminated(status) => {
-            println!("[server] terminated with status: {:?}", status);
-        }
-        _ => {}
-    }
-}
-
-fn find_dev_cli_path() -> String {
-    // In development, cli.js is in the project root (parent of src-tauri)
-    let possible_paths = [
-        "../cli.js",
-        "../../cli.js",
-        "cli.js",
-    ];
-
-    for path in &possible_paths {
-        if std::path::Path::new(path).exists() {
-            return path.to_string();
-        }
-    }
-
-    // Default
-    "../cli.js".to_string()
+        });
 }
@@ -0,0 +1,76 @@
+//! User preferences shared with the Node server
+//!
+//! Mirrors `ConfigUIServer.loadConfig` in `ui/server.cjs`, which reads
+//! `~/.claude-config/config.json` and fills in defaults for missing keys.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::util::expand_home;
+
+/// Port the UI server listens on when nothing else is configured
+pub const DEFAULT_PORT: u16 = 3333;
+
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Preferences {
+    pub ui: UiPreferences,
+    registry_path: Option<String>,
+    /// Tools `apply` generates config for
+    pub enabled_tools: Vec<String>,
+    /// Re-apply in the background when config changes (desktop app only)
+    pub auto_apply: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            ui: UiPreferences::default(),
+            registry_path: None,
+            enabled_tools: vec!["claude".to_string()],
+            auto_apply: false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct UiPreferences {
+    pub port: u16,
+}
+
+impl Default for UiPreferences {
+    fn default() -> Self {
+        Self { port: DEFAULT_PORT }
+    }
+}
+
+impl Preferences {
+    /// Path of the preferences file under the given home directory
+    pub fn path(home: &Path) -> PathBuf {
+        home.join(".claude-config").join("config.json")
+    }
+
+    /// Global MCP registry, `~/.claude/registry.json` unless overridden
+    pub fn registry_path(&self, home: &Path) -> PathBuf {
+        match &self.registry_path {
+            Some(path) => expand_home(path, home),
+            None => home.join(".claude").join("registry.json"),
+        }
+    }
+
+    /// Load preferences, falling back to defaults if the file is missing or invalid
+    pub fn load(home: &Path) -> Self {
+        let path = Self::path(home);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Error loading {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+}
@@ -0,0 +1,50 @@
+//! Registered projects
+//!
+//! Reads `projects.json` from the install directory, like
+//! `loadProjectsRegistry` in `lib/projects.js`, so the host knows the active
+//! project without asking the server.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::util::load_json;
+
+/// `CLAUDE_CONFIG_HOME`, or `~/.claude-config`, as in `config-loader.js`
+pub fn install_dir(home: &Path) -> PathBuf {
+    match std::env::var_os("CLAUDE_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home.join(".claude-config"),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProjectsRegistry {
+    pub projects: Vec<Project>,
+    pub active_project_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub path: PathBuf,
+}
+
+impl ProjectsRegistry {
+    /// Load the registry, treating a missing or invalid file as empty
+    pub fn load(home: &Path) -> Self {
+        let path = install_dir(home).join("projects.json");
+        load_json(&path)
+            .unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                None
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn active(&self) -> Option<&Project> {
+        let id = self.active_project_id.as_ref()?;
+        self.projects.iter().find(|p| p.id == *id)
+    }
+}
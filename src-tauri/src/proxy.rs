@@ -0,0 +1,237 @@
+//! `app://` protocol of the UI
+//!
+//! The windows load the UI from the app's own assets, and the host forwards
+//! their `/api/*` requests to the server over its private socket, adding the
+//! launch token. The server has no TCP port for the UI, so nothing else can
+//! reach the API, ports cannot collide, and there are no cross-origin
+//! requests. Assets are served with the CSP from `tauri.conf.json`.
+//!
+//! Protocol handlers hand the webview whole bodies, so a response is decoded
+//! from the socket into one buffer as it arrives and passed on as soon as its
+//! last byte is in, without waiting for the server to close the connection.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::time::Duration;
+
+use tauri::http::header::CONTENT_TYPE;
+use tauri::http::{Request, Response, StatusCode};
+use tauri::{Manager, UriSchemeResponder};
+
+use crate::endpoint;
+use crate::error::{Error, Result};
+use crate::health::TOKEN_HEADER;
+use crate::sidecar::ServerSupervisor;
+
+pub const SCHEME: &str = "app";
+
+/// How long a request may take; update checks wait on the network
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Longest status line, header or chunk size line accepted from the server
+const MAX_LINE: usize = 64 * 1024;
+
+/// Request headers the proxy sets itself
+const SKIPPED_HEADERS: &[&str] = &["host", "connection", "content-length", "transfer-encoding", "cookie"];
+
+/// Origin of the UI
+pub fn origin() -> &'static str {
+    // WebView2 serves custom protocols as http://<scheme>.localhost
+    if cfg!(windows) {
+        "http://app.localhost"
+    } else {
+        "app://localhost"
+    }
+}
+
+/// Answer a request of the webview without blocking it
+pub fn handle(app: &tauri::AppHandle, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let response = if request.uri().path().starts_with("/api/") {
+            forward(&app, &request).unwrap_or_else(|e| {
+                log::error!("Failed to forward {} {}: {}", request.method(), request.uri().path(), e);
+                error_response(StatusCode::BAD_GATEWAY, &e.to_string())
+            })
+        } else {
+            asset(&app, request.uri().path())
+        };
+        responder.respond(response);
+    });
+}
+
+/// Serve a file of `ui/dist`, or `index.html` for the UI's own routes
+fn asset(app: &tauri::AppHandle, path: &str) -> Response<Vec<u8>> {
+    let resolver = app.asset_resolver();
+    let Some(asset) = resolver
+        .get(path.to_string())
+        .or_else(|| resolver.get("index.html".to_string()))
+    else {
+        return error_response(StatusCode::NOT_FOUND, "Not found");
+    };
+
+    let mut response = Response::builder().header(CONTENT_TYPE, asset.mime_type());
+    if let Some(csp) = asset.csp_header() {
+        response = response.header("Content-Security-Policy", csp);
+    }
+    response
+        .body(asset.bytes().to_vec())
+        .unwrap_or_else(|e| error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
+}
+
+/// Send a request to the server and return its response
+fn forward(app: &tauri::AppHandle, request: &Request<Vec<u8>>) -> Result<Response<Vec<u8>>> {
+    let supervisor = app.state::<ServerSupervisor>();
+    let endpoint = supervisor
+        .endpoint()
+        .ok_or_else(|| Error::Invalid("Server is not running".to_string()))?;
+    let mut stream = endpoint.connect(REQUEST_TIMEOUT)?;
+
+    let path = request.uri().path_and_query().map_or("/", |p| p.as_str());
+    let mut head = format!("{} {} HTTP/1.1\r\nHost: {}\r\n", request.method(), path, endpoint.host());
+    for (name, value) in request.headers() {
+        if SKIPPED_HEADERS.contains(&name.as_str()) || name.as_str().eq_ignore_ascii_case(TOKEN_HEADER) {
+            continue;
+        }
+        if let Ok(value) = value.to_str() {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+    }
+    head.push_str(&format!(
+        "{}: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        TOKEN_HEADER,
+        supervisor.token(),
+        request.body().len()
+    ));
+
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(request.body())?;
+    read_response(stream.as_mut())
+}
+
+fn invalid_response() -> Error {
+    Error::Invalid("Invalid response from server".to_string())
+}
+
+/// Read the server's HTTP/1.1 response
+fn read_response(stream: impl Read) -> Result<Response<Vec<u8>>> {
+    let mut reader = BufReader::new(stream);
+
+    let status: u16 = read_line(&mut reader)?
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid_response)?;
+
+    let mut response = Response::builder().status(status);
+    let mut chunked = false;
+    let mut length = None;
+    loop {
+        let line = read_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            length = Some(value.parse::<usize>().map_err(|_| invalid_response())?);
+        } else if !name.eq_ignore_ascii_case("connection") {
+            response = response.header(name, value);
+        }
+    }
+
+    let body = if chunked {
+        read_chunked(&mut reader)?
+    } else if let Some(length) = length {
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        body
+    } else {
+        endpoint::read_to_end(&mut reader)?
+    };
+    response.body(body).map_err(|_| invalid_response())
+}
+
+/// Read a body sent with `Transfer-Encoding: chunked`
+fn read_chunked(reader: &mut impl BufRead) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| invalid_response())?;
+        if size == 0 {
+            // Skip trailers up to the blank line
+            while !read_line(reader)?.is_empty() {}
+            return Ok(body);
+        }
+
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        if !read_line(reader)?.is_empty() {
+            return Err(invalid_response());
+        }
+    }
+}
+
+/// A line of the head or the chunk framing, without its line break
+fn read_line(reader: &mut impl BufRead) -> Result<String> {
+    let mut line = String::new();
+    reader.by_ref().take(MAX_LINE as u64).read_line(&mut line)?;
+    let line = line.strip_suffix('\n').ok_or_else(invalid_response)?;
+    Ok(line.strip_suffix('\r').unwrap_or(line).to_string())
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Vec<u8>> {
+    let body = serde_json::json!({ "error": message }).to_string();
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.into_bytes())
+        .expect("valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(raw: &[u8]) -> (StatusCode, Vec<u8>) {
+        let response = read_response(raw).unwrap();
+        (response.status(), response.into_body())
+    }
+
+    #[test]
+    fn body_ends_at_its_length() {
+        let raw = b"HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}ignored";
+        let response = read_response(&raw[..]).unwrap();
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert!(response.headers().get("content-length").is_none());
+        assert_eq!(body(raw), (StatusCode::CREATED, b"{}".to_vec()));
+    }
+
+    #[test]
+    fn chunked_bodies_are_decoded() {
+        let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4;ext=1\r\nWiki\r\nA\r\n pedia in \r\n0\r\nX-Trailer: 1\r\n\r\nignored";
+        assert_eq!(body(raw), (StatusCode::OK, b"Wiki pedia in ".to_vec()));
+    }
+
+    #[test]
+    fn body_without_length_runs_to_the_end() {
+        assert_eq!(body(b"HTTP/1.1 200 OK\n\nplain"), (StatusCode::OK, b"plain".to_vec()));
+    }
+
+    #[test]
+    fn truncated_responses_are_refused() {
+        for raw in [
+            &b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort"[..],
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nab",
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/plain",
+            b"garbage",
+        ] {
+            assert!(read_response(raw).is_err());
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! Global MCP registry
+//!
+//! Native counterparts of `registryList`, `registryAdd` and `registryRemove`
+//! in `lib/registry.js`, so the desktop app can manage the registry without
+//! the server.
+
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::Manager;
+
+use crate::error::{Error, Result};
+use crate::preferences::Preferences;
+use crate::util::{load_json, save_json};
+
+/// Transports understood by the supported tools
+const TRANSPORTS: &[&str] = &["stdio", "sse", "http"];
+
+/// Contents of the registry file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Registry {
+    #[serde(default)]
+    pub mcp_servers: IndexMap<String, McpServer>,
+
+    /// Other top-level keys, written back untouched
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A single `mcpServers` entry
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpServer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+
+    /// Kept as written: hand-edited registries hold numbers and booleans here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<Value>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<String>,
+
+    /// Tool-specific keys such as `headers` or `cwd`, written back untouched
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A scalar `args` or `env` value as the process sees it
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+impl McpServer {
+    /// Arguments as strings, skipping any that are not scalars
+    pub fn arg_strings(&self) -> Vec<String> {
+        let args = self.args.as_ref().and_then(Value::as_array);
+        args.into_iter().flatten().filter_map(scalar_string).collect()
+    }
+
+    /// Environment as strings, skipping values that are not scalars
+    pub fn env_vars(&self) -> IndexMap<String, String> {
+        let env = self.env.as_ref().and_then(Value::as_object);
+        env.into_iter()
+            .flatten()
+            .filter_map(|(key, value)| Some((key.clone(), scalar_string(value)?)))
+            .collect()
+    }
+
+    /// Check that the entry describes a server some tool can start
+    pub fn validate(&self) -> Result<()> {
+        let command = self.command.as_deref().map(str::trim).filter(|c| !c.is_empty());
+        let url = self.url.as_deref().map(str::trim).filter(|u| !u.is_empty());
+
+        match self.transport.as_deref() {
+            None if command.is_none() && url.is_none() => {
+                return Err(Error::Invalid("MCP needs a \"command\" or a \"url\"".to_string()));
+            }
+            Some("stdio") if command.is_none() => {
+                return Err(Error::Invalid("stdio MCP needs a \"command\"".to_string()));
+            }
+            Some(transport @ ("sse" | "http")) if url.is_none() => {
+                return Err(Error::Invalid(format!("{} MCP needs a \"url\"", transport)));
+            }
+            Some(transport) if !TRANSPORTS.contains(&transport) => {
+                return Err(Error::Invalid(format!(
+                    "Unknown MCP type \"{}\" (expected one of: {})",
+                    transport,
+                    TRANSPORTS.join(", ")
+                )));
+            }
+            _ => {}
+        }
+
+        if let Some(url) = url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(Error::Invalid(format!("MCP url must be http(s): {}", url)));
+            }
+        }
+
+        let env = self.env.as_ref().and_then(Value::as_object);
+        if let Some(key) = env.into_iter().flat_map(Map::keys).find(|k| k.trim().is_empty() || k.contains('=')) {
+            return Err(Error::Invalid(format!("Invalid environment variable name \"{}\"", key)));
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that a name is usable as an `mcpServers` key and in `include` lists
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(Error::Invalid("MCP name is required".to_string()));
+    }
+    // `lib/registry.js` takes any name; only point out the unusual ones
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+        log::warn!("MCP name \"{}\" has characters some tools may not accept", name);
+    }
+    Ok(())
+}
+
+impl Registry {
+    /// Load the registry, treating a missing file as empty
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(load_json(path)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        save_json(path, self)
+    }
+}
+
+/// Location of the registry for the current user
+pub fn registry_path(app: &tauri::AppHandle) -> Result<PathBuf> {
+    let home = app.path().home_dir()?;
+    Ok(Preferences::load(&home).registry_path(&home))
+}
+
+/// List registry entries, sorted by name like `registryList`
+pub fn list(path: &Path) -> Result<IndexMap<String, McpServer>> {
+    let mut servers = Registry::load(path)?.mcp_servers;
+    servers.sort_keys();
+    Ok(servers)
+}
+
+/// Add or replace a registry entry
+pub fn add(path: &Path, name: &str, server: McpServer) -> Result<()> {
+    validate_name(name)?;
+    server.validate()?;
+
+    let mut registry = Registry::load(path)?;
+    registry.mcp_servers.insert(name.to_string(), server);
+    registry.save(path)?;
+
+    log::info!("Added \"{}\" to registry {}", name, path.display());
+    Ok(())
+}
+
+/// Remove a registry entry
+pub fn remove(path: &Path, name: &str) -> Result<()> {
+    let mut registry = Registry::load(path)?;
+    if registry.mcp_servers.shift_remove(name).is_none() {
+        return Err(Error::NotInRegistry(name.to_string()));
+    }
+    registry.save(path)?;
+
+    log::info!("Removed \"{}\" from registry {}", name, path.display());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn registry_list(app: tauri::AppHandle) -> Result<IndexMap<String, McpServer>> {
+    list(&registry_path(&app)?)
+}
+
+#[tauri::command]
+pub fn registry_add(app: tauri::AppHandle, name: String, config: McpServer) -> Result<()> {
+    add(&registry_path(&app)?, name.trim(), config)
+}
+
+#[tauri::command]
+pub fn registry_remove(app: tauri::AppHandle, name: String) -> Result<()> {
+    remove(&registry_path(&app)?, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn server(config: Value) -> McpServer {
+        serde_json::from_value(config).unwrap()
+    }
+
+    #[test]
+    fn missing_registry_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = Registry::load(&dir.path().join("registry.json")).unwrap();
+        assert!(registry.mcp_servers.is_empty());
+    }
+
+    #[test]
+    fn non_string_args_and_env_are_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+        let config = json!({
+            "mcpServers": {
+                "db": {"command": "db-mcp", "args": ["--port", 5432], "env": {"DEBUG": true}}
+            }
+        });
+        std::fs::write(&path, config.to_string()).unwrap();
+
+        let servers = list(&path).unwrap();
+        assert_eq!(servers["db"].args, Some(json!(["--port", 5432])));
+        assert_eq!(servers["db"].env, Some(json!({"DEBUG": true})));
+        assert_eq!(servers["db"].arg_strings(), ["--port", "5432"]);
+        assert_eq!(servers["db"].env_vars()["DEBUG"], "true");
+    }
+
+    #[test]
+    fn unknown_keys_survive_a_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+        let config = json!({
+            "mcpServers": {
+                "remote": {"type": "http", "url": "https://example.com/mcp", "headers": {"X-Key": "${KEY}"}}
+            },
+            "comment": "kept"
+        });
+        std::fs::write(&path, config.to_string()).unwrap();
+
+        Registry::load(&path).unwrap().save(&path).unwrap();
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved, config);
+    }
+
+    #[test]
+    fn add_and_remove_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+
+        add(&path, "zeta", server(json!({"command": "zeta-mcp"}))).unwrap();
+        add(&path, "alpha", server(json!({"url": "https://example.com/mcp"}))).unwrap();
+        assert_eq!(list(&path).unwrap().keys().collect::<Vec<_>>(), ["alpha", "zeta"]);
+
+        remove(&path, "zeta").unwrap();
+        assert_eq!(list(&path).unwrap().keys().collect::<Vec<_>>(), ["alpha"]);
+        assert!(matches!(remove(&path, "zeta"), Err(Error::NotInRegistry(_))));
+    }
+
+    #[test]
+    fn invalid_entries_are_not_added() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+
+        assert!(add(&path, "", server(json!({"command": "x"}))).is_err());
+        assert!(add(&path, "empty", server(json!({}))).is_err());
+        assert!(!path.exists());
+
+        // Unusual names are accepted, as the CLI does
+        add(&path, "my server", server(json!({"command": "x"}))).unwrap();
+    }
+
+    #[test]
+    fn entries_are_validated() {
+        let invalid = [
+            json!({"type": "stdio", "url": "https://example.com"}),
+            json!({"type": "sse", "command": "x"}),
+            json!({"type": "ws", "url": "https://example.com"}),
+            json!({"url": "ftp://example.com"}),
+            json!({"command": "x", "env": {"A=B": "1"}}),
+        ];
+        for config in invalid {
+            assert!(server(config.clone()).validate().is_err(), "{}", config);
+        }
+
+        let valid = [
+            json!({"command": "x", "args": [1, true], "env": {"PORT": 80}}),
+            json!({"type": "http", "url": "http://localhost:3000/mcp"}),
+        ];
+        for config in valid {
+            assert!(server(config.clone()).validate().is_ok(), "{}", config);
+        }
+    }
+}
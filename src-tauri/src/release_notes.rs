@@ -0,0 +1,114 @@
+//! Release notes window
+//!
+//! Offers an update in a window of its own rather than a message dialog, so
+//! the full markdown notes, and the entries of the offered release's
+//! `CHANGELOG.md` since the installed version, can be read before deciding. The window
+//! shows the server UI with `?view=release-notes`; it fetches the notes with
+//! [`release_notes`] and sends the decision back with [`answer_update`].
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_updater::Update;
+
+use crate::error::Result;
+use crate::sidecar::ServerSupervisor;
+use crate::updater::Choice;
+
+/// Label of the release notes window
+const LABEL: &str = "release-notes";
+
+/// Notes of the update being offered
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNotes {
+    pub current_version: String,
+    pub version: String,
+    /// Markdown from the update manifest
+    pub body: Option<String>,
+}
+
+/// The offer shown in the window, if any
+#[derive(Default)]
+pub struct ReleaseNotesState {
+    notes: Mutex<Option<ReleaseNotes>>,
+    /// Dropped when the window closes, which counts as "later"
+    answer: Mutex<Option<Sender<Choice>>>,
+}
+
+/// Show the notes of `update` and wait for a decision
+///
+/// `None` if the window cannot be opened, e.g. while the server is down.
+pub async fn ask(app: &tauri::AppHandle, update: &Update) -> Option<Choice> {
+    let url = app
+        .state::<ServerSupervisor>()
+        .window_url(&[("view", "release-notes")])?;
+
+    let (tx, rx) = mpsc::channel();
+    let state = app.state::<ReleaseNotesState>();
+    *state.notes.lock().unwrap() = Some(ReleaseNotes {
+        current_version: update.current_version.clone(),
+        version: update.version.clone(),
+        body: update.body.clone(),
+    });
+    *state.answer.lock().unwrap() = Some(tx);
+
+    if let Err(e) = open_window(app, url, &update.version) {
+        log::error!("Failed to open release notes: {}", e);
+        clear(app);
+        return None;
+    }
+
+    let choice = tauri::async_runtime::spawn_blocking(move || rx.recv().unwrap_or(Choice::Later))
+        .await
+        .unwrap_or(Choice::Later);
+    clear(app);
+    Some(choice)
+}
+
+fn open_window(app: &tauri::AppHandle, url: tauri::Url, version: &str) -> Result<()> {
+    let window = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::External(url))
+        .title(format!("Update to {}", version))
+        .inner_size(640.0, 720.0)
+        .min_inner_size(480.0, 400.0)
+        .center()
+        .build()?;
+
+    let handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            handle.state::<ReleaseNotesState>().answer.lock().unwrap().take();
+        }
+    });
+    Ok(())
+}
+
+fn clear(app: &tauri::AppHandle) {
+    let state = app.state::<ReleaseNotesState>();
+    state.notes.lock().unwrap().take();
+    state.answer.lock().unwrap().take();
+}
+
+/// Notes of the update on offer, for the release notes window
+#[tauri::command]
+pub fn release_notes(state: tauri::State<'_, ReleaseNotesState>) -> Option<ReleaseNotes> {
+    state.notes.lock().unwrap().clone()
+}
+
+/// Decision from the release notes window, which is closed afterwards
+#[tauri::command]
+pub fn answer_update(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, ReleaseNotesState>,
+    choice: Choice,
+) -> Result<()> {
+    if let Some(answer) = state.answer.lock().unwrap().take() {
+        let _ = answer.send(choice);
+    }
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.close()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,335 @@
+//! Rollback of updates that fail to start
+//!
+//! Before an update is installed, the installed bundle (the `.app` on macOS,
+//! the AppImage on Linux) is copied aside.
+//! Launches of the new version are counted until the server passes its
+//! startup health check, which confirms the update and deletes the copy.
+//! After [`MAX_FAILED_LAUNCHES`] launches without one, the copy is put back,
+//! the broken version is skipped and the user is told what happened.
+//!
+//! Windows installs are not covered: the installer replaces a folder under
+//! Program Files that only an elevated process may write, and the previous
+//! installer is not kept to run again. Neither are Linux packages other than
+//! the AppImage. The preferences say so on those installs.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+use crate::error::Result;
+use crate::updater::UpdateSettings;
+use crate::util::{load_json, save_json};
+
+/// Launches of a new version that may fail before it is rolled back
+const MAX_FAILED_LAUNCHES: u32 = 3;
+
+/// An installed update that has not started successfully yet
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Probation {
+    version: String,
+    previous_version: String,
+    bundle: PathBuf,
+    backup: PathBuf,
+    launches: u32,
+}
+
+fn dir(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(app.path().app_data_dir()?.join("rollback"))
+}
+
+fn probation_path(app: &tauri::AppHandle) -> Result<PathBuf> {
+    Ok(dir(app)?.join("rollback.json"))
+}
+
+fn load(app: &tauri::AppHandle) -> Option<Probation> {
+    probation_path(app)
+        .and_then(|path| load_json(&path))
+        .unwrap_or_else(|e| {
+            log::warn!("Error loading rollback state: {}", e);
+            None
+        })
+}
+
+/// What the updater replaces, or `None` for installs that cannot be rolled
+/// back
+fn bundle_path() -> Option<PathBuf> {
+    if cfg!(target_os = "linux") {
+        return std::env::var_os("APPIMAGE").map(PathBuf::from);
+    }
+    if cfg!(target_os = "macos") {
+        return std::env::current_exe()
+            .ok()?
+            .ancestors()
+            .find(|p| p.extension().is_some_and(|ext| ext == "app"))
+            .map(Path::to_path_buf);
+    }
+    None
+}
+
+/// Whether a failed update of this install can be rolled back
+#[tauri::command]
+pub fn rollback_supported() -> bool {
+    bundle_path().is_some()
+}
+
+/// Copy the installed bundle aside before `version` is installed
+pub fn backup(app: &tauri::AppHandle, version: &str) -> Result<()> {
+    let Some(bundle) = bundle_path() else {
+        log::warn!("Rollback is not supported on this install, update {} cannot be rolled back", version);
+        return Ok(());
+    };
+
+    let dir = dir(app)?;
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    let backup = dir.join(bundle.file_name().unwrap_or_default());
+    copy_all(&bundle, &backup)?;
+    log::info!("Backed up {} to {}", bundle.display(), backup.display());
+
+    let probation = Probation {
+        version: version.to_string(),
+        previous_version: app.package_info().version.to_string(),
+        bundle,
+        backup,
+        launches: 0,
+    };
+    save_json(&probation_path(app)?, &probation)
+}
+
+/// Count a launch of an update on probation, rolling it back if too many
+/// launches died before the server came up
+pub fn on_launch(app: &tauri::AppHandle) {
+    let Some(mut probation) = load(app) else {
+        return;
+    };
+
+    // The update was never installed, or another version replaced it
+    if probation.version != app.package_info().version.to_string() {
+        discard(app);
+        return;
+    }
+
+    if probation.launches >= MAX_FAILED_LAUNCHES {
+        roll_back(app, &probation);
+    }
+
+    probation.launches += 1;
+    log::info!(
+        "Launch {}/{} of update {}",
+        probation.launches,
+        MAX_FAILED_LAUNCHES,
+        probation.version
+    );
+    if let Err(e) = probation_path(app).and_then(|path| save_json(&path, &probation)) {
+        log::error!("Failed to save rollback state: {}", e);
+    }
+}
+
+/// The server passed its startup health check, so the update works
+pub fn confirm(app: &tauri::AppHandle) {
+    if let Some(probation) = load(app) {
+        log::info!("Update {} started successfully", probation.version);
+        discard(app);
+    }
+}
+
+/// The server failed to start; roll back if this was the last allowed launch
+pub fn on_failed_start(app: &tauri::AppHandle) {
+    if let Some(probation) = load(app) {
+        if probation.launches >= MAX_FAILED_LAUNCHES {
+            roll_back(app, &probation);
+        }
+    }
+}
+
+fn discard(app: &tauri::AppHandle) {
+    if let Err(e) = dir(app).and_then(|dir| Ok(std::fs::remove_dir_all(dir)?)) {
+        log::warn!("Failed to remove rollback backup: {}", e);
+    }
+}
+
+/// Put the backup in place of the broken bundle and restart
+///
+/// Returns only if the bundle could not be replaced.
+fn roll_back(app: &tauri::AppHandle, probation: &Probation) {
+    log::error!(
+        "Update {} failed to start {} times, restoring {}",
+        probation.version,
+        probation.launches,
+        probation.previous_version
+    );
+
+    if let Err(e) = restore(probation) {
+        log::error!("Rollback failed: {}", e);
+        app.dialog()
+            .message(format!(
+                "Version {} failed to start, and version {} could not be restored: {}\n\nA copy of it is kept in {}.",
+                probation.version,
+                probation.previous_version,
+                e,
+                probation.backup.display()
+            ))
+            .kind(MessageDialogKind::Error)
+            .title("Rollback Failed")
+            .blocking_show();
+        return;
+    }
+
+    // Do not offer the broken version again
+    let mut settings = UpdateSettings::load(app);
+    settings.skipped_version = Some(probation.version.clone());
+    if let Err(e) = settings.save(app) {
+        log::error!("Failed to save updater settings: {}", e);
+    }
+    discard(app);
+
+    app.dialog()
+        .message(format!(
+            "Version {} failed to start {} times, so version {} has been restored.\n\nThe logs have the details. You will be offered the next version when it is released.",
+            probation.version, probation.launches, probation.previous_version
+        ))
+        .kind(MessageDialogKind::Warning)
+        .title("Update Rolled Back")
+        .blocking_show();
+
+    app.restart();
+}
+
+/// Move the broken bundle aside and copy the backup in its place
+fn restore(probation: &Probation) -> Result<()> {
+    let bundle = &probation.bundle;
+    let mut failed = bundle.clone().into_os_string();
+    failed.push(format!(".failed-{}", probation.version));
+    let failed = PathBuf::from(failed);
+
+    std::fs::rename(bundle, &failed)?;
+    if let Err(e) = copy_all(&probation.backup, bundle) {
+        // Leave the broken bundle rather than none
+        let _ = remove_all(bundle);
+        let _ = std::fs::rename(&failed, bundle);
+        return Err(e);
+    }
+    if let Err(e) = remove_all(&failed) {
+        log::warn!("Failed to remove {}: {}", failed.display(), e);
+    }
+    Ok(())
+}
+
+fn remove_all(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Copy a file or folder, keeping symlinks as links, as bundles rely on them
+fn copy_all(from: &Path, to: &Path) -> Result<()> {
+    let meta = std::fs::symlink_metadata(from)?;
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(from)?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&target, to)?;
+        #[cfg(windows)]
+        std::fs::copy(from.parent().unwrap_or(Path::new(".")).join(&target), to)?;
+    } else if meta.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        std::fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An installed bundle at version 2 and a backup of version 1
+    fn install(root: &Path) -> Probation {
+        let bundle = root.join("Claude Config.app");
+        let backup = root.join("rollback").join("Claude Config.app");
+        for (dir, version) in [(&bundle, "2"), (&backup, "1")] {
+            std::fs::create_dir_all(dir.join("Contents")).unwrap();
+            std::fs::write(dir.join("Contents").join("version"), version).unwrap();
+        }
+        Probation {
+            version: "2.0.0".to_string(),
+            previous_version: "1.0.0".to_string(),
+            bundle,
+            backup,
+            launches: MAX_FAILED_LAUNCHES,
+        }
+    }
+
+    fn installed_version(probation: &Probation) -> String {
+        std::fs::read_to_string(probation.bundle.join("Contents").join("version")).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_keep_symlinks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("from");
+        std::fs::create_dir_all(from.join("lib")).unwrap();
+        std::fs::write(from.join("lib").join("libapp.so.1"), "lib").unwrap();
+        std::os::unix::fs::symlink("libapp.so.1", from.join("lib").join("libapp.so")).unwrap();
+
+        let to = tmp.path().join("nested").join("to");
+        copy_all(&from, &to).unwrap();
+        assert_eq!(std::fs::read_to_string(to.join("lib").join("libapp.so.1")).unwrap(), "lib");
+        assert_eq!(
+            std::fs::read_link(to.join("lib").join("libapp.so")).unwrap(),
+            Path::new("libapp.so.1")
+        );
+    }
+
+    #[test]
+    fn restore_puts_the_backup_in_place() {
+        let tmp = tempfile::tempdir().unwrap();
+        let probation = install(tmp.path());
+
+        restore(&probation).unwrap();
+        assert_eq!(installed_version(&probation), "1");
+        // The broken bundle is not left behind
+        let names: Vec<_> = std::fs::read_dir(tmp.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names.len(), 2, "{:?}", names);
+    }
+
+    #[test]
+    fn missing_backup_leaves_the_install_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let probation = install(tmp.path());
+        std::fs::remove_dir_all(&probation.backup).unwrap();
+
+        assert!(restore(&probation).is_err());
+        assert_eq!(installed_version(&probation), "2");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_copy_leaves_the_install_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let probation = install(tmp.path());
+        // Neither a file, a folder nor a link, so copying stops partway
+        let socket = probation.backup.join("Contents").join("socket");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        assert!(restore(&probation).is_err());
+        assert_eq!(installed_version(&probation), "2");
+        assert!(!probation.bundle.join("Contents").join("socket").exists());
+        let names: Vec<_> = std::fs::read_dir(tmp.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names.len(), 2, "{:?}", names);
+    }
+}
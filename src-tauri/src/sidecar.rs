@@ -0,0 +1,622 @@
+//! Node.js server sidecar supervision
+//!
+//! The supervisor owns the server child process, restarts it with exponential
+//! backoff when it exits unexpectedly, and kills it when the app shuts down.
+//! The server's `/api/restart` asks the supervisor over the bridge and then
+//! exits, so the new process is again one the supervisor owns.
+//! On the first start, the bundled server must report the app's own version.
+//!
+//! With a window the server listens on a private socket behind the `app://`
+//! proxy; with `--headless` it listens on a loopback port for browsers.
+
+use std::collections::VecDeque;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tauri::async_runtime::Receiver;
+use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+
+use crate::bridge;
+use crate::cli::LaunchArgs;
+use crate::endpoint::Endpoint;
+use crate::health;
+use crate::preferences::{Preferences, DEFAULT_PORT};
+use crate::proxy;
+use crate::rollback;
+use crate::watcher::ConfigWatcher;
+
+/// Delay before the first restart, doubled after each consecutive crash
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound for the restart delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Consecutive crashes tolerated before giving up
+const MAX_RESTARTS: u32 = 5;
+
+/// A server that stays up this long is considered healthy again
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// How long the server may take to answer its health check
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay between health checks while waiting for the server
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Number of stderr lines kept for error reports
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Tells the server it runs in the desktop app, and which version
+const DESKTOP_ENV: &str = "CLAUDE_CONFIG_DESKTOP";
+
+/// Secret the server requires on every request, see `ui/routes/desktop-auth.js`
+const TOKEN_ENV: &str = "CLAUDE_CONFIG_TOKEN";
+
+type ServerProcess = (Receiver<CommandEvent>, CommandChild);
+
+/// Owns the running server process
+#[derive(Default)]
+pub struct ServerSupervisor {
+    child: Mutex<Option<CommandChild>>,
+    endpoint: OnceLock<Endpoint>,
+    token: OnceLock<String>,
+    stderr_tail: Mutex<VecDeque<String>>,
+    shutting_down: AtomicBool,
+    /// The server asked to be restarted and is about to exit
+    restart_requested: AtomicBool,
+}
+
+impl ServerSupervisor {
+    /// Stop restarting the server and kill the running process
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        if let Some(child) = self.child.lock().unwrap().take() {
+            if let Err(e) = child.kill() {
+                log::error!("Failed to stop server: {}", e);
+            }
+        }
+    }
+
+    /// URL of the UI, once the server's endpoint has been chosen
+    pub fn url(&self) -> Option<String> {
+        match self.endpoint.get()? {
+            Endpoint::Tcp(port) => Some(server_url(*port)),
+            Endpoint::Socket(_) => Some(proxy::origin().to_string()),
+        }
+    }
+
+    /// URL to open the UI at in a window, with extra query parameters
+    ///
+    /// Over TCP the server trades the token in it for a cookie and redirects,
+    /// while the proxy adds the token to each request itself.
+    pub fn window_url(&self, params: &[(&str, &str)]) -> Option<tauri::Url> {
+        let mut url = match tauri::Url::parse(&format!("{}/", self.url()?)) {
+            Ok(url) => url,
+            Err(e) => {
+                log::error!("Invalid UI URL: {}", e);
+                return None;
+            }
+        };
+
+        let mut params = params.to_vec();
+        if let Endpoint::Tcp(_) = self.endpoint.get()? {
+            params.push(("token", self.token()));
+        }
+        if !params.is_empty() {
+            url.query_pairs_mut().extend_pairs(params);
+        }
+        Some(url)
+    }
+
+    /// Where the server accepts HTTP requests
+    pub fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.get()
+    }
+
+    /// Secret for this launch, the same across restarts so the webview's
+    /// cookie stays valid
+    pub fn token(&self) -> &str {
+        self.token.get_or_init(new_token)
+    }
+
+    /// Write a line to the server's stdin
+    pub fn write_line(&self, line: &str) -> Result<(), tauri_plugin_shell::Error> {
+        let mut child = self.child.lock().unwrap();
+        let Some(child) = child.as_mut() else {
+            log::warn!("Server is not running");
+            return Ok(());
+        };
+        child.write(format!("{}\n", line).as_bytes())
+    }
+
+    /// Start the server again as soon as it exits, without counting a crash
+    pub fn request_restart(&self) {
+        self.restart_requested.store(true, Ordering::SeqCst);
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    fn record_stderr(&self, line: String) {
+        let mut tail = self.stderr_tail.lock().unwrap();
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+
+    fn stderr_tail(&self) -> Vec<String> {
+        self.stderr_tail.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Run the server until the app exits, restarting it after crashes
+///
+/// The main window stays hidden until the first start passes its health
+/// check. Blocks the calling thread, so it must run on its own thread.
+pub fn supervise(app: &tauri::AppHandle) {
+    let supervisor = app.state::<ServerSupervisor>();
+    // Keep the same endpoint across restarts so the webview stays valid
+    let endpoint = supervisor.endpoint.get_or_init(|| choose_endpoint(app)).clone();
+    let startup_deadline = Instant::now() + STARTUP_TIMEOUT;
+    let mut failures = 0;
+    let mut backoff = INITIAL_BACKOFF;
+    let mut webview_attached = false;
+
+    while !supervisor.is_shutting_down() {
+        let started = Instant::now();
+
+        match start_server(app) {
+            Ok((rx, child)) => {
+                *supervisor.child.lock().unwrap() = Some(child);
+
+                // The app may have exited while the process was spawning
+                if supervisor.is_shutting_down() {
+                    supervisor.shutdown();
+                    return;
+                }
+
+                // Log output in background
+                let output_handle = app.clone();
+                let output = std::thread::spawn(move || wait_for_exit(&output_handle, rx));
+
+                let deadline = if webview_attached {
+                    Instant::now() + STARTUP_TIMEOUT
+                } else {
+                    startup_deadline
+                };
+
+                match wait_until_ready(&endpoint, supervisor.token(), deadline, &output) {
+                    Readiness::Ready if webview_attached => reload_webview(app),
+                    Readiness::Ready => {
+                        if let Err(message) = check_versions(app, &endpoint) {
+                            log::error!("{}", message);
+                            rollback::on_failed_start(app);
+                            app.dialog()
+                                .message(format!(
+                                    "{}\n\nPart of an update is missing. Please reinstall Claude Config from GitHub.",
+                                    message
+                                ))
+                                .kind(MessageDialogKind::Error)
+                                .title("Mixed Installation")
+                                .blocking_show();
+                            supervisor.shutdown();
+                            app.exit(1);
+                            return;
+                        }
+                        rollback::confirm(app);
+                        attach_webview(app);
+                        webview_attached = true;
+                    }
+                    Readiness::Exited => {}
+                    Readiness::TimedOut if webview_attached => {
+                        log::error!("Server not responding after restart");
+                    }
+                    Readiness::TimedOut => {
+                        log::error!("Server not ready after {}s", STARTUP_TIMEOUT.as_secs());
+                        rollback::on_failed_start(app);
+                        show_failure(
+                            app,
+                            "Server Not Responding",
+                            &format!(
+                                "The Claude Config server did not start within {} seconds.",
+                                STARTUP_TIMEOUT.as_secs()
+                            ),
+                        );
+                        supervisor.shutdown();
+                        app.exit(1);
+                        return;
+                    }
+                }
+
+                let _ = output.join();
+                supervisor.child.lock().unwrap().take();
+            }
+            Err(e) => {
+                log::error!("Failed to start server: {}", e);
+            }
+        }
+
+        if supervisor.is_shutting_down() {
+            return;
+        }
+
+        if supervisor.restart_requested.swap(false, Ordering::SeqCst) {
+            log::info!("Restarting server on request");
+            continue;
+        }
+
+        if started.elapsed() >= STABLE_UPTIME {
+            failures = 0;
+            backoff = INITIAL_BACKOFF;
+        }
+
+        failures += 1;
+        if failures > MAX_RESTARTS {
+            log::error!("Server crashed {} times in a row, giving up", MAX_RESTARTS);
+            if !webview_attached {
+                rollback::on_failed_start(app);
+            }
+            show_failure(
+                app,
+                "Server Stopped",
+                &format!(
+                    "The Claude Config server stopped unexpectedly and could not be restarted after {} attempts.",
+                    MAX_RESTARTS
+                ),
+            );
+            // Without a server the hidden window would never appear
+            if !webview_attached {
+                app.exit(1);
+            }
+            return;
+        }
+
+        log::warn!(
+            "Restarting server in {}ms (attempt {}/{})",
+            backoff.as_millis(),
+            failures,
+            MAX_RESTARTS
+        );
+        std::thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+enum Readiness {
+    Ready,
+    Exited,
+    TimedOut,
+}
+
+/// Version handshake: the bundled server must come from the same release as
+/// the app, or an update was only partly applied
+///
+/// The development server runs from the source tree and is not checked.
+fn check_versions(app: &tauri::AppHandle, endpoint: &Endpoint) -> Result<(), String> {
+    let bundled = app
+        .path()
+        .resource_dir()
+        .is_ok_and(|dir| dir.join("server").exists());
+    if !bundled {
+        return Ok(());
+    }
+
+    let app_version = app.package_info().version.to_string();
+    match health::server_version(endpoint, app.state::<ServerSupervisor>().token()) {
+        Some(server_version) if server_version == app_version => Ok(()),
+        Some(server_version) => Err(format!(
+            "The app is version {} but its server is version {}.",
+            app_version, server_version
+        )),
+        None => Err("The server did not report its version.".to_string()),
+    }
+}
+
+/// Poll the health endpoint until it answers, the process exits or time runs out
+fn wait_until_ready(
+    endpoint: &Endpoint,
+    token: &str,
+    deadline: Instant,
+    output: &std::thread::JoinHandle<()>,
+) -> Readiness {
+    loop {
+        if health::server_ready(endpoint, token) {
+            return Readiness::Ready;
+        }
+        if output.is_finished() {
+            return Readiness::Exited;
+        }
+        if Instant::now() >= deadline {
+            return Readiness::TimedOut;
+        }
+        std::thread::sleep(READY_POLL_INTERVAL);
+    }
+}
+
+/// Show an error dialog that includes the last lines the server wrote to stderr
+fn show_failure(app: &tauri::AppHandle, title: &str, message: &str) {
+    let tail = app.state::<ServerSupervisor>().stderr_tail();
+    let details = if tail.is_empty() {
+        "The server produced no error output.".to_string()
+    } else {
+        format!("Last server output:\n\n{}", tail.join("\n"))
+    };
+
+    app.dialog()
+        .message(format!("{}\n\n{}\n\nPlease restart the app.", message, details))
+        .kind(MessageDialogKind::Error)
+        .title(title)
+        .blocking_show();
+}
+
+/// URL of the server UI for the given port
+fn server_url(port: u16) -> String {
+    format!("http://localhost:{}", port)
+}
+
+/// Point the main window at the server and reveal it, unless launched with
+/// `--headless`
+fn attach_webview(app: &tauri::AppHandle) {
+    let Some(url) = app.state::<ServerSupervisor>().window_url(&[]) else {
+        return;
+    };
+
+    let args = app.state::<LaunchArgs>();
+    if args.headless {
+        // The query holds the launch token, which must stay out of the logs
+        let mut logged = url.clone();
+        logged.set_query(None);
+        log::info!("Running headless, UI available at {}", logged);
+        println!("UI available at {}", url);
+        return;
+    }
+
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+
+    if let Some(name) = args.dir.as_deref().and_then(|dir| dir.file_name()) {
+        let _ = window.set_title(&format!("Claude Config - {}", name.to_string_lossy()));
+    }
+
+    if let Err(e) = window.navigate(url) {
+        log::error!("Failed to load UI: {}", e);
+    }
+
+    if let Err(e) = window.show() {
+        log::error!("Failed to show window: {}", e);
+    }
+}
+
+/// Reload the UI after the server came back from a restart
+fn reload_webview(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.reload() {
+            log::error!("Failed to reload UI: {}", e);
+        }
+    }
+}
+
+/// Private socket behind the proxy for a window, or a loopback port for
+/// browsers when `--headless`
+fn choose_endpoint(app: &tauri::AppHandle) -> Endpoint {
+    if app.state::<LaunchArgs>().headless {
+        return Endpoint::Tcp(choose_port(app));
+    }
+    match socket_path(app) {
+        Ok(path) => Endpoint::Socket(path),
+        Err(e) => {
+            log::error!("No socket for the server, using a port: {}", e);
+            Endpoint::Tcp(choose_port(app))
+        }
+    }
+}
+
+/// A named pipe on Windows, otherwise a Unix socket in the app's data folder,
+/// which only the user can reach
+fn socket_path(app: &tauri::AppHandle) -> crate::error::Result<PathBuf> {
+    if cfg!(windows) {
+        return Ok(PathBuf::from(format!(r"\\.\pipe\claude-config-{}", std::process::id())));
+    }
+    let dir = app.path().app_local_data_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("server.sock"))
+}
+
+/// Use the port from `--port`, or else the configured UI port if it is free
+/// and any free port otherwise
+///
+/// The CLI's `claude-config ui` defaults to the same port, so a collision is
+/// expected whenever both are running.
+fn choose_port(app: &tauri::AppHandle) -> u16 {
+    if let Some(port) = app.state::<LaunchArgs>().port {
+        return port;
+    }
+
+    let preferred = app
+        .path()
+        .home_dir()
+        .map(|home| Preferences::load(&home).ui.port)
+        .unwrap_or(DEFAULT_PORT);
+
+    if port_available(preferred) {
+        return preferred;
+    }
+
+    match free_port() {
+        Some(port) => {
+            log::info!("Port {} is in use, using {}", preferred, port);
+            port
+        }
+        None => preferred,
+    }
+}
+
+/// 256 random bits, hex encoded
+fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).expect("no random number generator");
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn port_available(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+    // Something already accepting connections wins even if we could bind
+    if TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok() {
+        return false;
+    }
+    TcpListener::bind(addr).is_ok()
+}
+
+fn free_port() -> Option<u16> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .ok()
+}
+
+fn wait_for_exit(app: &tauri::AppHandle, mut rx: Receiver<CommandEvent>) {
+    while let Some(event) = rx.blocking_recv() {
+        let terminated = matches!(event, CommandEvent::Terminated(_));
+        handle_command_event(app, event);
+        if terminated {
+            break;
+        }
+    }
+}
+
+fn start_server(app: &tauri::AppHandle) -> Result<ServerProcess, Box<dyn std::error::Error>> {
+    // Get the resource directory where server files are bundled
+    let resource_dir = app.path().resource_dir()?;
+    let server_dir = resource_dir.join("server");
+
+    // Check if we're in production (bundled) or development mode
+    if server_dir.exists() {
+        // Production: use bundled sidecar (Node.js) and server script
+        start_production_server(app, &server_dir)
+    } else {
+        // Development: use system node and local cli.js
+        start_development_server(app)
+    }
+}
+
+fn start_production_server(app: &tauri::AppHandle, server_dir: &std::path::Path) -> Result<ServerProcess, Box<dyn std::error::Error>> {
+    let sidecar = app.shell().sidecar("node-server")?;
+    let cli_path = server_dir.join("cli.js");
+
+    let mut args = vec![
+        cli_path.to_string_lossy().to_string(),
+        "ui".to_string(),
+        "--foreground".to_string(),
+    ];
+    args.extend(server_args(app));
+
+    // Spawn the sidecar (Node.js) with the cli.js script as first argument
+    let process = sidecar
+        .args(args)
+        .env("NODE_PATH", server_dir.join("node_modules").to_string_lossy().to_string())
+        .env(DESKTOP_ENV, app.package_info().version.to_string())
+        .env(TOKEN_ENV, app.state::<ServerSupervisor>().token())
+        .spawn()?;
+
+    Ok(process)
+}
+
+fn start_development_server(app: &tauri::AppHandle) -> Result<ServerProcess, Box<dyn std::error::Error>> {
+    let cli_path = find_dev_cli_path();
+
+    let mut args = vec![
+        cli_path,
+        "ui".to_string(),
+        "--foreground".to_string(),
+    ];
+    args.extend(server_args(app));
+
+    let shell = app.shell();
+    let process = shell
+        .command("node")
+        .args(args)
+        .env(DESKTOP_ENV, app.package_info().version.to_string())
+        .env(TOKEN_ENV, app.state::<ServerSupervisor>().token())
+        .spawn()?;
+
+    Ok(process)
+}
+
+/// Arguments after `ui`: the port, or the socket behind the proxy, and
+/// `--dir` for the project being watched, i.e. the one from the command line
+/// or a link at startup, and after a restart the one the UI was showing
+fn server_args(app: &tauri::AppHandle) -> Vec<String> {
+    let mut args = Vec::new();
+    match app.state::<ServerSupervisor>().endpoint() {
+        Some(Endpoint::Tcp(port)) => {
+            args.push("--port".to_string());
+            args.push(port.to_string());
+        }
+        Some(Endpoint::Socket(path)) => {
+            args.push("--socket".to_string());
+            args.push(path.to_string_lossy().to_string());
+        }
+        None => {}
+    }
+    if let Some(dir) = app.state::<ConfigWatcher>().dir() {
+        args.push("--dir".to_string());
+        args.push(dir.to_string_lossy().to_string());
+    }
+    args
+}
+
+fn handle_command_event(app: &tauri::AppHandle, event: CommandEvent) {
+    let supervisor = app.state::<ServerSupervisor>();
+    match event {
+        CommandEvent::Stdout(line) => {
+            if let Ok(s) = String::from_utf8(line) {
+                match bridge::request_json(&s) {
+                    Some(request) => bridge::handle(app, request),
+                    None => log::info!(target: "server", "{}", s),
+                }
+            }
+        }
+        CommandEvent::Stderr(line) => {
+            if let Ok(s) = String::from_utf8(line) {
+                log::warn!(target: "server", "{}", s);
+                supervisor.record_stderr(s);
+            }
+        }
+        CommandEvent::Error(e) => {
+            log::error!(target: "server", "{}", e);
+            supervisor.record_stderr(e);
+        }
+        CommandEvent::Terminated(status) => {
+            log::info!(target: "server", "terminated with status: {:?}", status);
+        }
+        _ => {}
+    }
+}
+
+fn find_dev_cli_path() -> String {
+    // In development, cli.js is in the project root (parent of src-tauri)
+    let possible_paths = [
+        "../cli.js",
+        "../../cli.js",
+        "cli.js",
+    ];
+
+    for path in &possible_paths {
+        if std::path::Path::new(path).exists() {
+            return path.to_string();
+        }
+    }
+
+    // Default
+    "../cli.js".to_string()
+}
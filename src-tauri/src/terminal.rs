@@ -0,0 +1,241 @@
+//! Shells of the embedded terminal
+//!
+//! The host starts the user's shell in a pseudo-terminal, so neither the
+//! webview nor the server spawns processes for it. The page only picks the
+//! directory, then writes keystrokes and resizes; output and the exit code
+//! come back as `terminal-output` and `terminal-exit` events.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use portable_pty::{ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::error::{Error, Result};
+
+const TERMINAL_OUTPUT: &str = "terminal-output";
+const TERMINAL_EXIT: &str = "terminal-exit";
+
+/// Shell for new sessions, as `ui/terminal-server.cjs` picked it
+fn shell() -> String {
+    std::env::var("SHELL").unwrap_or_else(|_| {
+        if cfg!(windows) { "powershell.exe" } else { "bash" }.to_string()
+    })
+}
+
+/// Writes to the shell, shared so a slow one blocks only its own session
+type SessionWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+
+struct Session {
+    master: Box<dyn MasterPty + Send>,
+    writer: SessionWriter,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Already gone if it exited by itself
+        let _ = self.killer.kill();
+    }
+}
+
+/// Open terminal sessions by id
+#[derive(Default)]
+pub struct TerminalState {
+    sessions: Mutex<HashMap<u32, Session>>,
+    next_id: AtomicU32,
+}
+
+impl TerminalState {
+    fn with_session<T>(&self, id: u32, f: impl FnOnce(&mut Session) -> Result<T>) -> Result<T> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get_mut(&id)
+            .ok_or_else(|| Error::Invalid(format!("No terminal session {}", id)))?;
+        f(session)
+    }
+
+    fn writer(&self, id: u32) -> Result<SessionWriter> {
+        self.with_session(id, |session| Ok(session.writer.clone()))
+    }
+
+    fn remove(&self, id: u32) -> Option<Session> {
+        self.sessions.lock().unwrap().remove(&id)
+    }
+
+    /// Kill every shell, when the app exits
+    pub fn close_all(&self) {
+        self.sessions.lock().unwrap().clear();
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct TerminalOutput {
+    id: u32,
+    data: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalExit {
+    id: u32,
+    exit_code: u32,
+}
+
+fn pty_error(e: impl std::fmt::Display) -> Error {
+    Error::Terminal(e.to_string())
+}
+
+fn size(cols: u16, rows: u16) -> PtySize {
+    PtySize {
+        rows: rows.max(1),
+        cols: cols.max(1),
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+/// Length of `bytes` without a UTF-8 character cut off at the end; invalid
+/// bytes count as complete, to be replaced
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    // The last character starts within the final 3 bytes if it is cut off
+    for back in 1..=bytes.len().min(3) {
+        let start = bytes.len() - back;
+        let width = match bytes[start] {
+            0x80..=0xBF => continue,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if width > back { start } else { bytes.len() };
+    }
+    bytes.len()
+}
+
+/// Send the shell's output as events until it closes the terminal
+fn forward_output(app: &tauri::AppHandle, id: u32, mut reader: Box<dyn Read + Send>) {
+    let mut buf = [0u8; 8192];
+    let mut pending = Vec::new();
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        pending.extend_from_slice(&buf[..n]);
+
+        // Hold back a character split across reads
+        let len = complete_utf8_len(&pending);
+        if len == 0 {
+            continue;
+        }
+        let data = String::from_utf8_lossy(&pending[..len]).into_owned();
+        pending.drain(..len);
+        if let Err(e) = app.emit(TERMINAL_OUTPUT, TerminalOutput { id, data }) {
+            log::warn!("Failed to emit {}: {}", TERMINAL_OUTPUT, e);
+        }
+    }
+}
+
+/// Start a shell in `cwd`; resolves to the session id
+#[tauri::command]
+pub fn terminal_open(app: tauri::AppHandle, cwd: Option<String>, cols: u16, rows: u16) -> Result<u32> {
+    let cwd = match cwd {
+        Some(cwd) => std::path::absolute(cwd)?,
+        None => app.path().home_dir()?,
+    };
+    if !cwd.is_dir() {
+        return Err(Error::Invalid(format!("Not a directory: {}", cwd.display())));
+    }
+
+    let pair = portable_pty::native_pty_system()
+        .openpty(size(cols, rows))
+        .map_err(pty_error)?;
+    let mut command = CommandBuilder::new(shell());
+    command.cwd(&cwd);
+    command.env("TERM", "xterm-256color");
+    command.env("COLORTERM", "truecolor");
+    let mut child = pair.slave.spawn_command(command).map_err(pty_error)?;
+    // The child holds its own handle; ours would keep the terminal open
+    drop(pair.slave);
+
+    let reader = pair.master.try_clone_reader().map_err(pty_error)?;
+    let writer = pair.master.take_writer().map_err(pty_error)?;
+    let state = app.state::<TerminalState>();
+    let id = state.next_id.fetch_add(1, Ordering::Relaxed);
+    state.sessions.lock().unwrap().insert(
+        id,
+        Session {
+            master: pair.master,
+            writer: Arc::new(Mutex::new(writer)),
+            killer: child.clone_killer(),
+        },
+    );
+    log::info!("Terminal session {} started in {}", id, cwd.display());
+
+    let output_app = app.clone();
+    std::thread::spawn(move || forward_output(&output_app, id, reader));
+    std::thread::spawn(move || {
+        let exit_code = match child.wait() {
+            Ok(status) => status.exit_code(),
+            Err(e) => {
+                log::warn!("Failed to wait for terminal session {}: {}", id, e);
+                1
+            }
+        };
+        log::info!("Terminal session {} exited with {}", id, exit_code);
+        app.state::<TerminalState>().remove(id);
+        if let Err(e) = app.emit(TERMINAL_EXIT, TerminalExit { id, exit_code }) {
+            log::warn!("Failed to emit {}: {}", TERMINAL_EXIT, e);
+        }
+    });
+
+    Ok(id)
+}
+
+/// Keystrokes for the shell
+#[tauri::command]
+pub fn terminal_write(state: tauri::State<'_, TerminalState>, id: u32, data: String) -> Result<()> {
+    // Not under the sessions lock: a shell that stops reading must not
+    // hold up the others
+    let writer = state.writer(id)?;
+    let mut writer = writer.lock().unwrap();
+    writer.write_all(data.as_bytes())?;
+    Ok(writer.flush()?)
+}
+
+#[tauri::command]
+pub fn terminal_resize(state: tauri::State<'_, TerminalState>, id: u32, cols: u16, rows: u16) -> Result<()> {
+    state.with_session(id, |session| session.master.resize(size(cols, rows)).map_err(pty_error))
+}
+
+/// Kill the shell; `terminal-exit` follows
+#[tauri::command]
+pub fn terminal_close(state: tauri::State<'_, TerminalState>, id: u32) {
+    if state.remove(id).is_some() {
+        log::info!("Terminal session {} closed", id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_characters_are_held_back() {
+        let text = "ö→".as_bytes();
+        assert_eq!(complete_utf8_len(text), text.len());
+        assert_eq!(complete_utf8_len(&text[..1]), 0);
+        assert_eq!(complete_utf8_len(&text[..3]), 2);
+        assert_eq!(complete_utf8_len(&text[..4]), 2);
+    }
+
+    #[test]
+    fn invalid_bytes_are_passed_on() {
+        assert_eq!(complete_utf8_len(b"ok\xff"), 3);
+        assert_eq!(complete_utf8_len(b"\xffok\xe2\x86"), 3);
+    }
+}
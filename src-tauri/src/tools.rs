@@ -0,0 +1,405 @@
+//! Per-tool apply targets
+//!
+//! Each supported CLI tool reads MCP servers from its own files and format.
+//! Where they live comes from `lib/tool-paths.json`, the table behind
+//! `TOOL_PATHS` in the Node CLI, and each [`ToolTarget`] turns the resolved
+//! servers into that tool's files. Supporting another tool means adding its
+//! entry to the table and one impl to [`TARGETS`].
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::apply::{ApplyContext, FileChange};
+use crate::config::{self, ConfigLayer, MergedConfig};
+use crate::error::{Error, Result};
+use crate::util::{expand_home, load_json, to_json_string};
+
+/// Where a tool keeps its config, as in `TOOL_PATHS`
+///
+/// Keys only the Node side uses (icons, rules folders) are ignored.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolPaths {
+    /// Key in the table
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+    /// Per-project folder holding `mcps.json` and `.env`
+    pub project_folder: String,
+    /// Global `mcps.json` merged below the project ones
+    pub global_mcp_config: Option<String>,
+    /// Global `.env`, read before the per-project ones
+    pub global_env: Option<String>,
+    /// Generated file, relative to the project unless it starts with `~`
+    pub output_file: String,
+    /// Whether the tool expands `${VAR}` itself at launch
+    pub supports_env_interpolation: bool,
+}
+
+impl ToolPaths {
+    pub fn output_path(&self, ctx: &ApplyContext) -> PathBuf {
+        if self.output_file.starts_with('~') {
+            expand_home(&self.output_file, ctx.home)
+        } else {
+            ctx.dir.join(&self.output_file)
+        }
+    }
+}
+
+static TOOL_PATHS: LazyLock<IndexMap<String, ToolPaths>> = LazyLock::new(|| {
+    let mut table: IndexMap<String, ToolPaths> =
+        serde_json::from_str(include_str!("../../lib/tool-paths.json"))
+            .expect("lib/tool-paths.json is a valid tool table");
+    for (id, paths) in &mut table {
+        paths.id = id.clone();
+    }
+    table
+});
+
+/// The `TOOL_PATHS` entry for a tool with an impl in [`TARGETS`]
+fn tool_paths(id: &str) -> &'static ToolPaths {
+    &TOOL_PATHS[id]
+}
+
+/// A tool `apply` can generate config for
+pub trait ToolTarget: Sync {
+    fn paths(&self) -> &'static ToolPaths;
+
+    /// Whether a project without config for this tool is an error rather
+    /// than a skipped tool
+    fn requires_config(&self) -> bool {
+        false
+    }
+
+    /// Config files merged for this tool, root first
+    fn find_configs(&self, ctx: &ApplyContext) -> Vec<(PathBuf, PathBuf)> {
+        let paths = self.paths();
+        let global_config = paths.global_mcp_config.as_deref().map(|p| expand_home(p, ctx.home));
+        config::find_tool_configs(&paths.project_folder, global_config.as_deref(), ctx.dir, ctx.home)
+    }
+
+    /// `.env` files read for interpolation, later ones overriding earlier
+    fn env_files(&self, ctx: &ApplyContext, layers: &[ConfigLayer]) -> Vec<PathBuf> {
+        let paths = self.paths();
+        let mut files: Vec<PathBuf> = paths
+            .global_env
+            .as_deref()
+            .map(|p| expand_home(p, ctx.home))
+            .into_iter()
+            .collect();
+        files.extend(
+            layers
+                .iter()
+                .filter(|layer| layer.dir != ctx.home)
+                .map(|layer| layer.dir.join(&paths.project_folder).join(".env")),
+        );
+        files
+    }
+
+    /// Files to write for the resolved servers
+    fn render(
+        &self,
+        ctx: &ApplyContext,
+        merged: &MergedConfig,
+        servers: &IndexMap<String, Value>,
+    ) -> Result<Vec<FileChange>>;
+}
+
+/// Every supported tool, in the order the UI lists them
+pub const TARGETS: &[&dyn ToolTarget] = &[&Claude, &Gemini, &Antigravity, &Codex];
+
+/// Look up a tool by its `TOOL_PATHS` id
+pub fn target(id: &str) -> Result<&'static dyn ToolTarget> {
+    TARGETS
+        .iter()
+        .copied()
+        .find(|t| t.paths().id == id)
+        .ok_or_else(|| Error::UnknownTool(id.to_string()))
+}
+
+fn json_file(path: PathBuf, value: &impl serde::Serialize) -> Result<FileChange> {
+    let content = to_json_string(&path, value)?;
+    FileChange::new(path, content)
+}
+
+/// Replace `mcpServers` in a JSON settings file, keeping its other keys
+fn merge_json_file(path: PathBuf, servers: &IndexMap<String, Value>) -> Result<FileChange> {
+    let mut settings: Map<String, Value> = load_json(&path)?.unwrap_or_default();
+    settings.insert("mcpServers".to_string(), json!(servers));
+    json_file(path, &settings)
+}
+
+/// `.mcp.json` in the project, plus `enabledPlugins` in `.claude/settings.json`
+pub struct Claude;
+
+impl ToolTarget for Claude {
+    fn paths(&self) -> &'static ToolPaths {
+        tool_paths("claude")
+    }
+
+    fn requires_config(&self) -> bool {
+        true
+    }
+
+    fn find_configs(&self, ctx: &ApplyContext) -> Vec<(PathBuf, PathBuf)> {
+        config::find_all_configs(ctx.dir, ctx.home)
+    }
+
+    fn env_files(&self, ctx: &ApplyContext, layers: &[ConfigLayer]) -> Vec<PathBuf> {
+        let registry_dir = ctx.registry_path.parent().unwrap_or(Path::new("."));
+        std::iter::once(registry_dir.join(".env"))
+            .chain(layers.iter().map(|layer| layer.dir.join(".claude").join(".env")))
+            .collect()
+    }
+
+    fn render(
+        &self,
+        ctx: &ApplyContext,
+        merged: &MergedConfig,
+        servers: &IndexMap<String, Value>,
+    ) -> Result<Vec<FileChange>> {
+        let mut files = vec![json_file(
+            self.paths().output_path(ctx),
+            &json!({ "mcpServers": servers }),
+        )?];
+
+        // Merge enabledPlugins into the existing settings, keeping other keys
+        if !merged.enabled_plugins.is_empty() {
+            let settings_path = ctx.dir.join(".claude").join("settings.json");
+            let mut settings: Map<String, Value> = load_json(&settings_path)?.unwrap_or_default();
+            let plugins: Map<String, Value> = merged
+                .enabled_plugins
+                .iter()
+                .map(|(name, enabled)| (name.clone(), Value::Bool(enabled.value)))
+                .collect();
+            settings.insert("enabledPlugins".to_string(), Value::Object(plugins));
+            files.push(json_file(settings_path, &settings)?);
+        }
+
+        Ok(files)
+    }
+}
+
+/// `mcpServers` in `~/.gemini/settings.json` and `.gemini/settings.json`
+pub struct Gemini;
+
+impl ToolTarget for Gemini {
+    fn paths(&self) -> &'static ToolPaths {
+        tool_paths("gemini")
+    }
+
+    fn render(
+        &self,
+        ctx: &ApplyContext,
+        _merged: &MergedConfig,
+        servers: &IndexMap<String, Value>,
+    ) -> Result<Vec<FileChange>> {
+        // The project file only holds the servers, unlike the global one
+        let project_path = ctx.dir.join(&self.paths().project_folder).join("settings.json");
+        Ok(vec![
+            merge_json_file(self.paths().output_path(ctx), servers)?,
+            json_file(project_path, &json!({ "mcpServers": servers }))?,
+        ])
+    }
+}
+
+/// `~/.gemini/antigravity/mcp_config.json`, with variables resolved
+pub struct Antigravity;
+
+impl ToolTarget for Antigravity {
+    fn paths(&self) -> &'static ToolPaths {
+        tool_paths("antigravity")
+    }
+
+    fn render(
+        &self,
+        ctx: &ApplyContext,
+        _merged: &MergedConfig,
+        servers: &IndexMap<String, Value>,
+    ) -> Result<Vec<FileChange>> {
+        Ok(vec![json_file(
+            self.paths().output_path(ctx),
+            &json!({ "mcpServers": servers }),
+        )?])
+    }
+}
+
+/// `[mcp_servers]` in `~/.codex/config.toml`, with variables resolved
+pub struct Codex;
+
+impl ToolTarget for Codex {
+    fn paths(&self) -> &'static ToolPaths {
+        tool_paths("codex")
+    }
+
+    fn render(
+        &self,
+        ctx: &ApplyContext,
+        _merged: &MergedConfig,
+        servers: &IndexMap<String, Value>,
+    ) -> Result<Vec<FileChange>> {
+        let path = self.paths().output_path(ctx);
+        let mut settings = load_toml(&path)?;
+
+        // Only `mcp_servers` is rewritten; other keys keep their comments
+        // and layout
+        let mut mcp_servers = toml_edit::Table::new();
+        mcp_servers.set_implicit(true);
+        for (name, server) in servers {
+            let fields = match server {
+                Value::Object(fields) => fields,
+                _ => {
+                    return Err(Error::Invalid(format!(
+                        "Cannot write MCP \"{}\" to {}: not an object",
+                        name,
+                        path.display()
+                    )))
+                }
+            };
+            let mut table = toml_edit::Table::new();
+            // Codex infers the transport from `command` or `url`
+            for (key, value) in fields.iter().filter(|(key, _)| *key != "type") {
+                let value = toml_value(value).ok_or_else(|| {
+                    Error::Invalid(format!(
+                        "Cannot write MCP \"{}\" to {}: \"{}\" has a null value",
+                        name,
+                        path.display(),
+                        key
+                    ))
+                })?;
+                table.insert(key, toml_edit::Item::Value(value));
+            }
+            mcp_servers.insert(name, toml_edit::Item::Table(table));
+        }
+        settings.insert("mcp_servers", toml_edit::Item::Table(mcp_servers));
+
+        Ok(vec![FileChange::new(path, settings.to_string())?])
+    }
+}
+
+/// Load a TOML file for editing, treating a missing file as empty
+fn load_toml(path: &Path) -> Result<toml_edit::DocumentMut> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+        Err(e) => return Err(e.into()),
+    };
+
+    content.parse().map_err(|source| Error::InvalidToml {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// A JSON value as an inline TOML value; `None` for nulls, which TOML lacks
+fn toml_value(value: &Value) -> Option<toml_edit::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64()?.into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(items) => items
+            .iter()
+            .map(toml_value)
+            .collect::<Option<toml_edit::Array>>()?
+            .into(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| Some((key.clone(), toml_value(value)?)))
+            .collect::<Option<toml_edit::InlineTable>>()?
+            .into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::EnvVars;
+
+    fn render_codex(home: &Path, servers: Value) -> String {
+        let secrets = EnvVars::new();
+        let ctx = ApplyContext {
+            dir: home,
+            home,
+            registry_path: &home.join(".claude").join("registry.json"),
+            secrets: &secrets,
+            process_env: |_| None,
+        };
+        let servers: IndexMap<String, Value> = serde_json::from_value(servers).unwrap();
+        let files = Codex.render(&ctx, &MergedConfig::default(), &servers).unwrap();
+        assert_eq!(files[0].path, home.join(".codex").join("config.toml"));
+        files[0].content.clone()
+    }
+
+    #[test]
+    fn every_tool_in_the_table_has_a_target() {
+        let ids: Vec<&str> = TARGETS.iter().map(|t| t.paths().id.as_str()).collect();
+        let table: Vec<&str> = TOOL_PATHS.keys().map(String::as_str).collect();
+        assert_eq!(ids, table);
+    }
+
+    #[test]
+    fn codex_replaces_only_mcp_servers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = tmp.path().join(".codex").join("config.toml");
+        std::fs::create_dir_all(config.parent().unwrap()).unwrap();
+        std::fs::write(
+            &config,
+            "# Picked by hand\nmodel = \"gpt-5-codex\"  # fast enough\n\n\
+             [mcp_servers.old]\ncommand = \"old\"\n\n\
+             [profiles.work]\napproval_policy = \"never\"\n",
+        )
+        .unwrap();
+
+        let content = render_codex(
+            tmp.path(),
+            json!({
+                "github": {
+                    "type": "stdio",
+                    "command": "npx",
+                    "args": ["-y", "server-github"],
+                    "env": { "GITHUB_TOKEN": "secret" }
+                }
+            }),
+        );
+        assert!(content.starts_with("# Picked by hand\nmodel = \"gpt-5-codex\"  # fast enough\n"));
+        assert!(content.contains("[profiles.work]\napproval_policy = \"never\"\n"));
+        assert!(!content.contains("old"));
+        assert!(!content.contains("type"));
+        assert!(content.contains(
+            "[mcp_servers.github]\ncommand = \"npx\"\nargs = [\"-y\", \"server-github\"]\n\
+             env = { GITHUB_TOKEN = \"secret\" }\n"
+        ));
+    }
+
+    #[test]
+    fn codex_creates_a_missing_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let content = render_codex(tmp.path(), json!({ "remote": { "url": "https://example.com/mcp" } }));
+        assert_eq!(content, "[mcp_servers.remote]\nurl = \"https://example.com/mcp\"\n");
+    }
+
+    #[test]
+    fn codex_refuses_null_values() {
+        let tmp = tempfile::tempdir().unwrap();
+        let secrets = EnvVars::new();
+        let ctx = ApplyContext {
+            dir: tmp.path(),
+            home: tmp.path(),
+            registry_path: tmp.path(),
+            secrets: &secrets,
+            process_env: |_| None,
+        };
+        let servers: IndexMap<String, Value> =
+            serde_json::from_value(json!({ "broken": { "command": null } })).unwrap();
+        let err = Codex.render(&ctx, &MergedConfig::default(), &servers).unwrap_err();
+        assert!(matches!(err, Error::Invalid(message) if message.contains("\"command\" has a null value")));
+    }
+}
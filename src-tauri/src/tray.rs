@@ -0,0 +1,199 @@
+//! System tray
+//!
+//! Switches the active workstream and runs quick actions without opening the
+//! window. The menu lists the workstreams from `workstreams.json`, with the
+//! active one checked, and is rebuilt whenever that file changes.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::Manager;
+
+use crate::apply::{self, ApplyContext};
+use crate::auto_apply;
+use crate::env;
+use crate::error::Result;
+use crate::instance;
+use crate::logging;
+use crate::preferences::Preferences;
+use crate::projects::{install_dir, ProjectsRegistry};
+use crate::vault::VaultState;
+use crate::watcher::ConfigWatcher;
+use crate::workstreams::{self, Workstreams};
+
+const TRAY_ID: &str = "main";
+
+/// Prefix of the menu ids of workstreams
+const WORKSTREAM_PREFIX: &str = "workstream:";
+
+const APPLY: &str = "apply";
+const OPEN_LOGS: &str = "open-logs";
+const SHOW: &str = "show";
+const QUIT: &str = "quit";
+
+/// Watcher that keeps the menu in sync with `workstreams.json`; only held, so
+/// it lives as long as the app
+struct WorkstreamsWatcher {
+    _watcher: Mutex<RecommendedWatcher>,
+}
+
+/// Add the tray icon
+pub fn init(app: &tauri::AppHandle) -> Result<()> {
+    let home = app.path().home_dir()?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Claude Config")
+        .menu(&menu(app, &home)?)
+        .show_menu_on_left_click(true)
+        .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    watch_workstreams(app, home)
+}
+
+fn menu(app: &tauri::AppHandle, home: &Path) -> Result<Menu<tauri::Wry>> {
+    let menu = Menu::new(app)?;
+
+    let data = Workstreams::load(home);
+    if data.workstreams.is_empty() {
+        menu.append(&MenuItem::new(app, "No Workstreams", false, None::<&str>)?)?;
+    } else {
+        menu.append(&MenuItem::new(app, "Workstreams", false, None::<&str>)?)?;
+        for workstream in &data.workstreams {
+            let active = data.active_id.as_deref() == Some(workstream.id.as_str());
+            menu.append(&CheckMenuItem::with_id(
+                app,
+                format!("{}{}", WORKSTREAM_PREFIX, workstream.id),
+                &workstream.name,
+                true,
+                active,
+                None::<&str>,
+            )?)?;
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, APPLY, "Apply Current Project", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, OPEN_LOGS, "Open Logs", true, None::<&str>)?)?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, SHOW, "Show Claude Config", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, QUIT, "Quit", true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Rebuild the menu, e.g. after the workstreams changed
+fn refresh(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let result = app
+        .path()
+        .home_dir()
+        .map_err(Into::into)
+        .and_then(|home| menu(app, &home))
+        .and_then(|menu| Ok(tray.set_menu(Some(menu))?));
+    if let Err(e) = result {
+        log::warn!("Failed to update tray menu: {}", e);
+    }
+}
+
+fn on_menu_event(app: &tauri::AppHandle, id: &str) {
+    if let Some(workstream_id) = id.strip_prefix(WORKSTREAM_PREFIX) {
+        use_workstream(app, workstream_id);
+        return;
+    }
+
+    match id {
+        APPLY => {
+            let app = app.clone();
+            std::thread::spawn(move || apply_current_project(&app));
+        }
+        OPEN_LOGS => {
+            if let Err(e) = logging::open_log_dir(app) {
+                log::error!("Failed to open logs: {}", e);
+            }
+        }
+        SHOW => instance::focus_main_window(app),
+        QUIT => app.exit(0),
+        _ => {}
+    }
+}
+
+fn use_workstream(app: &tauri::AppHandle, id: &str) {
+    let result = app
+        .path()
+        .home_dir()
+        .map_err(Into::into)
+        .and_then(|home| Workstreams::activate(&home, id));
+    if let Err(e) = result {
+        log::error!("Failed to switch workstream: {}", e);
+        auto_apply::notify(app, "Workstream not switched", &e.to_string());
+    }
+    // Also undoes the check mark the click toggled if switching failed
+    refresh(app);
+}
+
+/// Apply the enabled tools to the project the UI shows, or else the active
+/// project of the registry
+fn apply_current_project(app: &tauri::AppHandle) {
+    let Ok(home) = app.path().home_dir() else {
+        return;
+    };
+    let dir: Option<PathBuf> = app
+        .state::<ConfigWatcher>()
+        .dir()
+        .or_else(|| ProjectsRegistry::load(&home).active().map(|p| p.path.clone()));
+    let Some(dir) = dir else {
+        auto_apply::notify(app, "Nothing to apply", "No project is active.");
+        return;
+    };
+
+    let preferences = Preferences::load(&home);
+    let registry_path = preferences.registry_path(&home);
+    let secrets = app.state::<VaultState>().secrets(&home);
+    let ctx = ApplyContext {
+        dir: &dir,
+        home: &home,
+        registry_path: &registry_path,
+        secrets: &secrets,
+        process_env: env::process_var,
+    };
+    let outcomes = apply::apply_tools_in(&ctx, &preferences.enabled_tools, false, false);
+
+    match auto_apply::summary(&outcomes) {
+        Some((title, body)) => auto_apply::notify(app, title, &body),
+        None => auto_apply::notify(
+            app,
+            "Config up to date",
+            &format!("Nothing changed in {}", dir.display()),
+        ),
+    }
+}
+
+/// Rebuild the menu when `workstreams.json` changes, e.g. from the CLI
+fn watch_workstreams(app: &tauri::AppHandle, home: PathBuf) -> Result<()> {
+    let dir = install_dir(&home);
+    let path = workstreams::path(&home);
+
+    let handle = app.clone();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        match result {
+            Ok(event) if !event.kind.is_access() && event.paths.contains(&path) => refresh(&handle),
+            Ok(_) => {}
+            Err(e) => log::warn!("Watch error: {}", e),
+        }
+    })?;
+    if dir.is_dir() {
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    }
+    app.manage(WorkstreamsWatcher {
+        _watcher: Mutex::new(watcher),
+    });
+    Ok(())
+}
@@ -0,0 +1,571 @@
+//! Desktop app updates
+//!
+//! Checks the release feed of the chosen channel, or a local source on
+//! machines without internet access, after startup and then periodically,
+//! and shows the release notes before installing. An update can be installed
+//! now, downloaded and installed when the app quits, or skipped until a
+//! newer version appears. The download reports its progress to the webview
+//! as `update-status` events and can be cancelled; the install step is
+//! reported separately.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tauri_plugin_dialog::{
+    DialogExt, MessageDialogBuilder, MessageDialogButtons, MessageDialogKind, MessageDialogResult,
+};
+use tauri_plugin_updater::{Update, UpdaterExt};
+use tokio::sync::oneshot;
+use tokio::task::AbortHandle;
+
+use crate::error::{Error, Result};
+use crate::local_feed::{self, LocalFeed};
+use crate::release_notes;
+use crate::rollback;
+use crate::util::{load_json, save_json};
+
+/// Event carrying an [`UpdateStatus`]
+pub const UPDATE_STATUS: &str = "update-status";
+
+/// Manifest of the beta channel; stable uses the endpoint in `tauri.conf.json`
+const BETA_ENDPOINT: &str =
+    "https://github.com/regression-io/claude-config/releases/download/beta/latest.json";
+
+/// Delay before the first check, so startup is not slowed down
+const STARTUP_DELAY: Duration = Duration::from_secs(3);
+
+/// How often the periodic check re-reads `checkIntervalHours`
+const SETTINGS_POLL: Duration = Duration::from_secs(60);
+
+/// Range of `checkIntervalHours`, besides 0 for startup only
+const CHECK_INTERVAL_HOURS: std::ops::RangeInclusive<u64> = 1..=168;
+
+/// Minimum time between two progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Release feed to follow
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+/// Updater preferences, kept in `updater.json` in the app config directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UpdateSettings {
+    pub channel: Channel,
+    /// Version the user chose to skip; newer versions are offered again
+    pub skipped_version: Option<String>,
+    /// Hours between background checks, 0 to check only at startup
+    pub check_interval_hours: u64,
+    /// Folder, `latest.json` or `file://` URL checked instead of the feed,
+    /// for machines without internet access
+    pub local_source: Option<String>,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self {
+            channel: Channel::Stable,
+            skipped_version: None,
+            check_interval_hours: 6,
+            local_source: None,
+        }
+    }
+}
+
+impl UpdateSettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf> {
+        Ok(app.path().app_config_dir()?.join("updater.json"))
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        Self::path(app)
+            .and_then(|path| load_json::<Self>(&path))
+            .unwrap_or_else(|e| {
+                log::warn!("Error loading updater settings: {}", e);
+                None
+            })
+            .unwrap_or_default()
+            .clamped()
+    }
+
+    pub fn save(&self, app: &tauri::AppHandle) -> Result<()> {
+        save_json(&Self::path(app)?, self)
+    }
+
+    /// Bring a hand-edited or out-of-range interval back within a week
+    fn clamped(mut self) -> Self {
+        if self.check_interval_hours != 0 {
+            self.check_interval_hours = self
+                .check_interval_hours
+                .clamp(*CHECK_INTERVAL_HOURS.start(), *CHECK_INTERVAL_HOURS.end());
+        }
+        self
+    }
+}
+
+/// Stage of an accepted update
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum UpdateStatus {
+    #[serde(rename_all = "camelCase")]
+    Downloading {
+        version: String,
+        downloaded: u64,
+        /// `None` when the server sends no content length
+        total: Option<u64>,
+    },
+    /// Downloaded, to be installed when the app quits
+    Pending { version: String },
+    Installing { version: String },
+    Installed { version: String },
+    Cancelled { version: String },
+    Failed { version: String, error: String },
+}
+
+/// What the user chose for an available update
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Choice {
+    Now,
+    OnQuit,
+    Skip,
+    Later,
+}
+
+/// Updater state shared by the background checks and the commands
+#[derive(Default)]
+pub struct UpdateState {
+    /// Set while an update is being offered or downloaded
+    busy: AtomicBool,
+    /// The download in progress, taken by [`cancel_update`]
+    download: Mutex<Option<AbortHandle>>,
+    /// Update downloaded for installation on quit
+    pending: Mutex<Option<(Update, Vec<u8>)>>,
+}
+
+fn emit_status(app: &tauri::AppHandle, status: UpdateStatus) {
+    if let Err(e) = app.emit(UPDATE_STATUS, status) {
+        log::warn!("Failed to emit {}: {}", UPDATE_STATUS, e);
+    }
+}
+
+/// An update found by a check
+struct Available {
+    update: Update,
+    /// Local source of the update, served until it has been downloaded
+    feed: Option<LocalFeed>,
+}
+
+/// Check the local source if one is configured, otherwise the feed of the
+/// configured channel
+async fn check(app: &tauri::AppHandle) -> Result<Option<Available>> {
+    let settings = UpdateSettings::load(app);
+    if let Some(source) = settings.local_source.as_deref().filter(|s| !s.is_empty()) {
+        return check_local(app, source).await;
+    }
+
+    let mut builder = app.updater_builder();
+    if settings.channel == Channel::Beta {
+        let endpoint = tauri::Url::parse(BETA_ENDPOINT).expect("valid beta endpoint");
+        builder = builder.endpoints(vec![endpoint])?;
+    }
+    let update = builder.build()?.check().await?;
+    Ok(update.map(|update| Available { update, feed: None }))
+}
+
+/// Check a local folder, manifest or `file://` URL
+async fn check_local(app: &tauri::AppHandle, source: &str) -> Result<Option<Available>> {
+    if !local_feed::is_supported(app) {
+        return Err(Error::Invalid(
+            "This build cannot update from a local source; use the offline build".to_string(),
+        ));
+    }
+    let feed = LocalFeed::start(source)?;
+    let update = app
+        .updater_builder()
+        .endpoints(vec![feed.endpoint()])?
+        .build()?
+        .check()
+        .await?;
+    Ok(update.map(|update| Available {
+        update,
+        feed: Some(feed),
+    }))
+}
+
+/// Check at startup, then every `checkIntervalHours` while it is not 0
+pub async fn run_periodic_checks(app: tauri::AppHandle) {
+    tokio::time::sleep(STARTUP_DELAY).await;
+
+    check_in_background(&app).await;
+    let mut last_check = Instant::now();
+
+    // Polled, so turning checks back on or shortening the interval applies
+    // without a restart
+    loop {
+        tokio::time::sleep(SETTINGS_POLL).await;
+
+        let hours = UpdateSettings::load(&app).check_interval_hours;
+        if hours == 0 || last_check.elapsed() < Duration::from_secs(hours.saturating_mul(60 * 60)) {
+            continue;
+        }
+        check_in_background(&app).await;
+        last_check = Instant::now();
+    }
+}
+
+async fn check_in_background(app: &tauri::AppHandle) {
+    let state = app.state::<UpdateState>();
+    if state.busy.load(Ordering::SeqCst) || state.pending.lock().unwrap().is_some() {
+        return;
+    }
+
+    match check(app).await {
+        Ok(Some(available)) => {
+            let version = &available.update.version;
+            let skipped = UpdateSettings::load(app).skipped_version;
+            if skipped.as_deref() == Some(version.as_str()) {
+                log::info!("Update {} is available but skipped", version);
+                return;
+            }
+            offer(app, available).await;
+        }
+        Ok(None) => {
+            log::info!("No updates available");
+        }
+        Err(e) => {
+            log::error!("Failed to check for updates: {}", e);
+        }
+    }
+}
+
+/// Ask the user what to do with an update and carry it out
+async fn offer(app: &tauri::AppHandle, available: Available) {
+    let state = app.state::<UpdateState>();
+    if state.pending.lock().unwrap().is_some() || state.busy.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    // A local feed keeps serving until this returns
+    let Available { update, feed: _feed } = available;
+
+    let choice = match release_notes::ask(app, &update).await {
+        Some(choice) => choice,
+        None => ask(app, &update).await,
+    };
+    log::info!("Update {}: user chose {:?}", update.version, choice);
+    match choice {
+        Choice::Now => {
+            if let Some(bytes) = download(app, &update).await {
+                install(app, &update, bytes).await;
+            }
+        }
+        Choice::OnQuit => {
+            if let Some(bytes) = download(app, &update).await {
+                emit_status(
+                    app,
+                    UpdateStatus::Pending {
+                        version: update.version.clone(),
+                    },
+                );
+                *state.pending.lock().unwrap() = Some((update, bytes));
+            }
+        }
+        Choice::Skip => {
+            let mut settings = UpdateSettings::load(app);
+            settings.skipped_version = Some(update.version.clone());
+            if let Err(e) = settings.save(app) {
+                log::error!("Failed to save updater settings: {}", e);
+            }
+        }
+        Choice::Later => {}
+    }
+
+    state.busy.store(false, Ordering::SeqCst);
+}
+
+/// Show a message dialog and wait for the answer without blocking the
+/// async runtime
+async fn show(dialog: MessageDialogBuilder<tauri::Wry>) -> MessageDialogResult {
+    let (tx, rx) = oneshot::channel();
+    dialog.show_with_result(move |result| {
+        let _ = tx.send(result);
+    });
+    rx.await.unwrap_or_default()
+}
+
+/// Ask with message dialogs when the release notes window is unavailable
+async fn ask(app: &tauri::AppHandle, update: &Update) -> Choice {
+    let body = update.body.clone().unwrap_or_default();
+    let dialog = app
+        .dialog()
+        .message(format!(
+            "A new version ({}) is available!\n\n{}\n\nInstall it now, or when you quit the app?",
+            update.version,
+            body.chars().take(200).collect::<String>()
+        ))
+        .kind(MessageDialogKind::Info)
+        .title("Update Available")
+        .buttons(MessageDialogButtons::YesNoCancelCustom(
+            "Update Now".to_string(),
+            "Install on Quit".to_string(),
+            "Not Now".to_string(),
+        ));
+
+    match show(dialog).await {
+        MessageDialogResult::Custom(label) if label == "Update Now" => return Choice::Now,
+        MessageDialogResult::Custom(label) if label == "Install on Quit" => return Choice::OnQuit,
+        _ => {}
+    }
+
+    // Closing the first dialog also lands here, so skipping takes a second,
+    // explicit answer
+    let dialog = app
+        .dialog()
+        .message(format!(
+            "Skip version {}? You will be told again when a newer version is released.",
+            update.version
+        ))
+        .kind(MessageDialogKind::Info)
+        .title("Update Available")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Skip This Version".to_string(),
+            "Remind Me Later".to_string(),
+        ));
+
+    match show(dialog).await {
+        MessageDialogResult::Custom(label) if label == "Skip This Version" => Choice::Skip,
+        _ => Choice::Later,
+    }
+}
+
+/// Download with progress events; `None` if cancelled or failed
+async fn download(app: &tauri::AppHandle, update: &Update) -> Option<Vec<u8>> {
+    let version = update.version.clone();
+
+    let mut downloaded = 0u64;
+    let mut last_emit: Option<Instant> = None;
+    let progress_app = app.clone();
+    let progress_version = version.clone();
+    let on_chunk = move |chunk: usize, total: Option<u64>| {
+        downloaded += chunk as u64;
+        let done = total == Some(downloaded);
+        if done || last_emit.is_none_or(|t| t.elapsed() >= PROGRESS_INTERVAL) {
+            last_emit = Some(Instant::now());
+            emit_status(
+                &progress_app,
+                UpdateStatus::Downloading {
+                    version: progress_version.clone(),
+                    downloaded,
+                    total,
+                },
+            );
+        }
+    };
+
+    // Run the download as its own task so cancelling can abort it
+    let download = update.clone();
+    let task = tauri::async_runtime::spawn(async move { download.download(on_chunk, || {}).await });
+    let state = app.state::<UpdateState>();
+    *state.download.lock().unwrap() = Some(task.inner().abort_handle());
+    let result = task.await;
+    let cancelled = state.download.lock().unwrap().take().is_none();
+
+    match result {
+        Ok(Ok(bytes)) => Some(bytes),
+        Ok(Err(e)) => {
+            report_failure(app, &version, e.to_string()).await;
+            None
+        }
+        Err(_) if cancelled => {
+            log::info!("Update to {} cancelled", version);
+            emit_status(app, UpdateStatus::Cancelled { version });
+            None
+        }
+        Err(e) => {
+            report_failure(app, &version, e.to_string()).await;
+            None
+        }
+    }
+}
+
+/// Install a downloaded update and restart
+async fn install(app: &tauri::AppHandle, update: &Update, bytes: Vec<u8>) {
+    let version = update.version.clone();
+
+    log::info!("Installing update {}", version);
+    emit_status(app, UpdateStatus::Installing { version: version.clone() });
+    if let Err(e) = rollback::backup(app, &version) {
+        log::warn!("Failed to back up the installed version: {}", e);
+    }
+    if let Err(e) = update.install(bytes) {
+        return report_failure(app, &version, e.to_string()).await;
+    }
+    emit_status(app, UpdateStatus::Installed { version: version.clone() });
+
+    show(
+        app.dialog()
+            .message("Update installed! The app will now restart.")
+            .kind(MessageDialogKind::Info)
+            .title("Update Complete"),
+    )
+    .await;
+
+    // Restart the app
+    app.restart();
+}
+
+/// Install an update deferred with "Install on Quit"; called on exit
+pub fn install_pending(app: &tauri::AppHandle) {
+    let Some((update, bytes)) = app.state::<UpdateState>().pending.lock().unwrap().take() else {
+        return;
+    };
+
+    log::info!("Installing update {} on quit", update.version);
+    if let Err(e) = rollback::backup(app, &update.version) {
+        log::warn!("Failed to back up the installed version: {}", e);
+    }
+    if let Err(e) = update.install(bytes) {
+        log::error!("Failed to install update: {}", e);
+    }
+}
+
+async fn report_failure(app: &tauri::AppHandle, version: &str, error: String) {
+    log::error!("Failed to install update: {}", error);
+    emit_status(
+        app,
+        UpdateStatus::Failed {
+            version: version.to_string(),
+            error: error.clone(),
+        },
+    );
+    show(
+        app.dialog()
+            .message(format!("Failed to install update: {}\n\nPlease download manually from GitHub.", error))
+            .kind(MessageDialogKind::Error)
+            .title("Update Failed"),
+    )
+    .await;
+}
+
+/// Result of a check requested from the UI
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCheck {
+    pub current_version: String,
+    /// Newer version on the configured channel, if any
+    pub version: Option<String>,
+}
+
+/// Offer an update found on request without waiting for the answer
+fn offer_in_background(app: &tauri::AppHandle, available: Option<Available>) -> UpdateCheck {
+    let current_version = app.package_info().version.to_string();
+    let Some(available) = available else {
+        return UpdateCheck {
+            current_version,
+            version: None,
+        };
+    };
+
+    let version = available.update.version.clone();
+    let offer_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        offer(&offer_app, available).await;
+    });
+
+    UpdateCheck {
+        current_version,
+        version: Some(version),
+    }
+}
+
+/// Check for updates without offering them
+pub async fn find_update(app: &tauri::AppHandle) -> Result<UpdateCheck> {
+    let available = check(app).await?;
+    Ok(UpdateCheck {
+        current_version: app.package_info().version.to_string(),
+        version: available.map(|a| a.update.version),
+    })
+}
+
+/// Check for updates now; an available update is offered even if skipped
+#[tauri::command]
+pub async fn check_for_updates(app: tauri::AppHandle) -> Result<UpdateCheck> {
+    let available = check(&app).await?;
+    Ok(offer_in_background(&app, available))
+}
+
+/// Update from a manifest picked in a file dialog, next to its package;
+/// `None` if the dialog was cancelled
+#[tauri::command]
+pub async fn update_from_file(app: tauri::AppHandle) -> Result<Option<UpdateCheck>> {
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Choose an update manifest")
+        .add_filter("Update manifest", &["json"])
+        .pick_file(move |picked| {
+            let _ = tx.send(picked);
+        });
+    let picked = rx.await.ok().flatten();
+    let Some(path) = picked.as_ref().and_then(|p| p.as_path()) else {
+        return Ok(None);
+    };
+
+    let available = check_local(&app, &path.to_string_lossy()).await?;
+    Ok(Some(offer_in_background(&app, available)))
+}
+
+#[tauri::command]
+pub fn update_settings(app: tauri::AppHandle) -> UpdateSettings {
+    UpdateSettings::load(&app)
+}
+
+#[tauri::command]
+pub fn set_update_settings(app: tauri::AppHandle, settings: UpdateSettings) -> Result<()> {
+    settings.clamped().save(&app)
+}
+
+/// Cancel the update download; `false` if none is running
+#[tauri::command]
+pub fn cancel_update(state: tauri::State<'_, UpdateState>) -> bool {
+    match state.download.lock().unwrap().take() {
+        Some(download) => {
+            download.abort();
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_interval(hours: u64) -> UpdateSettings {
+        UpdateSettings {
+            check_interval_hours: hours,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn check_interval_is_clamped_to_a_week() {
+        assert_eq!(with_interval(6).clamped().check_interval_hours, 6);
+        assert_eq!(with_interval(169).clamped().check_interval_hours, 168);
+        assert_eq!(with_interval(u64::MAX).clamped().check_interval_hours, 168);
+    }
+
+    #[test]
+    fn zero_still_means_startup_only() {
+        assert_eq!(with_interval(0).clamped().check_interval_hours, 0);
+    }
+}
@@ -0,0 +1,123 @@
+//! JSON file helpers, mirroring `loadJson`/`saveJson` in `lib/utils.js`
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+
+/// Load a JSON file, returning `None` if it does not exist
+///
+/// Unlike `loadJson`, a file that fails to parse is an error rather than
+/// `None`, so callers never overwrite a file they could not read.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|source| Error::InvalidJson {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Save a value as pretty-printed JSON with a trailing newline
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_atomic(path, &to_json_string(path, value)?)
+}
+
+/// Format a value the way `saveJson` does
+pub fn to_json_string<T: Serialize>(path: &Path, value: &T) -> Result<String> {
+    let mut content = serde_json::to_string_pretty(value).map_err(|source| Error::InvalidJson {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    content.push('\n');
+    Ok(content)
+}
+
+/// Suffix of the temporary files written by [`write_atomic`]
+const TMP_SUFFIX: &str = ".tmp";
+
+/// Whether `path` is a temporary `.{name}.{pid}.{n}.tmp` file of
+/// [`write_atomic`]
+pub fn is_atomic_tmp(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let Some(stem) = name.strip_prefix('.').and_then(|n| n.strip_suffix(TMP_SUFFIX)) else {
+        return false;
+    };
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let mut parts = stem.rsplitn(3, '.');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(n), Some(pid), Some(file)) => !file.is_empty() && is_number(pid) && is_number(n),
+        _ => false,
+    }
+}
+
+/// Numbers the temporary files of this process, so concurrent writes of the
+/// same file do not share one
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Write a file through a temporary sibling and a rename, so readers never
+/// see a partially written file
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let n = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp_path = dir.join(format!(".{}.{}.{}{}", file_name, std::process::id(), n, TMP_SUFFIX));
+
+    std::fs::write(&tmp_path, content)?;
+    if let Err(e) = std::fs::rename(&tmp_path, path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_home(path: &str, home: &Path) -> PathBuf {
+    if path == "~" {
+        return home.to_path_buf();
+    }
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_writes_of_one_file_do_not_collide() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        write_atomic(path, &format!("{}\n", i)).unwrap();
+                    }
+                });
+            }
+        });
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.trim().parse::<u32>().is_ok_and(|i| i < 8), "{}", content);
+        let names: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, ["settings.json"]);
+    }
+}
@@ -0,0 +1,597 @@
+//! Encrypted vault for MCP credentials
+//!
+//! API keys would otherwise sit in plaintext `.env` files, which are easy to
+//! commit by accident. The vault keeps them in `vault.json` in the install
+//! directory, encrypted with XChaCha20-Poly1305 under a key derived from a
+//! passphrase with Argon2id. The desktop app holds the key for the session
+//! once unlocked, and `apply` resolves `${VAR}` from the vault on top of the
+//! `.env` files. Commands and the `secrets` subcommand only ever list names.
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::env::EnvVars;
+use crate::error::{Error, Result};
+use crate::projects::install_dir;
+use crate::util::{load_json, save_json};
+
+const VAULT_FILE_NAME: &str = "vault.json";
+
+/// Version of the `vault.json` format
+const FORMAT_VERSION: u32 = 1;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Argon2id cost for new vaults, the OWASP recommendation; each vault
+/// records its own, so it can be raised without breaking old ones
+const MEMORY_KIB: u32 = 19 * 1024;
+const ITERATIONS: u32 = 2;
+const PARALLELISM: u32 = 1;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Passphrase for the `secrets` subcommand in scripts
+const PASSPHRASE_ENV: &str = "CLAUDE_CONFIG_VAULT_PASSPHRASE";
+
+pub const USAGE: &str = "\
+Usage: claude-config-desktop secrets <COMMAND>
+
+Commands:
+  list           Print the names of stored secrets
+  set <NAME>     Store a secret, prompted for or read from stdin
+  remove <NAME>  Delete a secret
+  rotate         Re-encrypt the vault under a new passphrase
+
+The passphrase is prompted for, or read from CLAUDE_CONFIG_VAULT_PASSPHRASE.";
+
+/// Contents of `vault.json`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    /// Base64, fresh for every write
+    nonce: String,
+    /// Base64 of the encrypted JSON object of secrets
+    ciphertext: String,
+}
+
+/// How the key is derived from the passphrase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KdfParams {
+    /// Base64
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl KdfParams {
+    fn generate() -> Self {
+        Self {
+            salt: BASE64.encode(random::<SALT_LEN>()),
+            memory_kib: MEMORY_KIB,
+            iterations: ITERATIONS,
+            parallelism: PARALLELISM,
+        }
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<VaultKey> {
+        let invalid = |e: argon2::Error| Error::Invalid(format!("Invalid vault parameters: {}", e));
+        let salt = decode("salt", &self.salt)?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(KEY_LEN))
+            .map_err(invalid)?;
+
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(invalid)?;
+        Ok(VaultKey {
+            key,
+            kdf: self.clone(),
+        })
+    }
+}
+
+/// Decrypted secrets, wiped from memory when dropped
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct Secrets(BTreeMap<String, String>);
+
+impl Drop for Secrets {
+    fn drop(&mut self) {
+        self.0.values_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+/// Key of an unlocked vault
+struct VaultKey {
+    key: Zeroizing<[u8; KEY_LEN]>,
+    kdf: KdfParams,
+}
+
+impl VaultKey {
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(Key::from_slice(self.key.as_ref()))
+    }
+
+    /// Read and decrypt the vault at `path`
+    fn load(&self, path: &Path) -> Result<Secrets> {
+        let file = read(path)?;
+        // Rotated since this key was derived
+        if file.kdf != self.kdf {
+            return Err(Error::VaultLocked);
+        }
+
+        let nonce = decode("nonce", &file.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(corrupt(format!("nonce is {} bytes, not {}", nonce.len(), NONCE_LEN)));
+        }
+        let ciphertext = decode("ciphertext", &file.ciphertext)?;
+        let plaintext = Zeroizing::new(
+            self.cipher()
+                .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+                .map_err(|_| Error::WrongPassphrase)?,
+        );
+        serde_json::from_slice(&plaintext).map_err(|source| Error::InvalidJson {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Encrypt `secrets` and write them to `path`
+    fn save(&self, path: &Path, secrets: &Secrets) -> Result<()> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(secrets).map_err(|source| Error::InvalidJson {
+            path: path.to_path_buf(),
+            source,
+        })?);
+        let nonce = random::<NONCE_LEN>();
+        let ciphertext = self
+            .cipher()
+            .encrypt(XNonce::from_slice(&nonce), plaintext.as_ref())
+            .map_err(|_| Error::Invalid("Failed to encrypt the vault".to_string()))?;
+
+        save_json(
+            path,
+            &VaultFile {
+                version: FORMAT_VERSION,
+                kdf: self.kdf.clone(),
+                nonce: BASE64.encode(nonce),
+                ciphertext: BASE64.encode(ciphertext),
+            },
+        )
+    }
+}
+
+/// `vault.json` in the install directory
+pub fn vault_path(home: &Path) -> PathBuf {
+    install_dir(home).join(VAULT_FILE_NAME)
+}
+
+fn read(path: &Path) -> Result<VaultFile> {
+    let file: VaultFile = load_json(path)?.ok_or_else(|| Error::NoVault(path.to_path_buf()))?;
+    if file.version != FORMAT_VERSION {
+        return Err(Error::Invalid(format!(
+            "Unsupported vault version {} in {}",
+            file.version,
+            path.display()
+        )));
+    }
+    Ok(file)
+}
+
+/// A vault file that cannot be what `create` or `set` wrote
+fn corrupt(reason: String) -> Error {
+    Error::Invalid(format!("The vault is corrupt: {}", reason))
+}
+
+fn decode(field: &str, value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| corrupt(format!("{} is not valid base64 ({})", field, e)))
+}
+
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    getrandom::fill(&mut bytes).expect("no random number generator");
+    bytes
+}
+
+fn unlock(path: &Path, passphrase: &str) -> Result<(VaultKey, Secrets)> {
+    let key = read(path)?.kdf.derive(passphrase)?;
+    let secrets = key.load(path)?;
+    Ok((key, secrets))
+}
+
+/// Create an empty vault at `path`
+fn create(path: &Path, passphrase: &str) -> Result<VaultKey> {
+    if path.exists() {
+        return Err(Error::Invalid(format!("A vault already exists at {}", path.display())));
+    }
+    validate_passphrase(passphrase)?;
+
+    let key = KdfParams::generate().derive(passphrase)?;
+    key.save(path, &Secrets::default())?;
+    log::info!("Created secrets vault {}", path.display());
+    Ok(key)
+}
+
+fn set(key: &VaultKey, path: &Path, name: &str, value: &str) -> Result<()> {
+    validate_name(name)?;
+    // Empty values count as unset when interpolating
+    if value.is_empty() {
+        return Err(Error::Invalid(format!("Secret \"{}\" cannot be empty", name)));
+    }
+
+    let mut secrets = key.load(path)?;
+    secrets.0.insert(name.to_string(), value.to_string());
+    key.save(path, &secrets)?;
+    log::info!("Stored secret \"{}\" in vault", name);
+    Ok(())
+}
+
+fn remove(key: &VaultKey, path: &Path, name: &str) -> Result<()> {
+    let mut secrets = key.load(path)?;
+    if secrets.0.remove(name).is_none() {
+        return Err(Error::Invalid(format!("\"{}\" is not in the vault", name)));
+    }
+    key.save(path, &secrets)?;
+    log::info!("Removed secret \"{}\" from vault", name);
+    Ok(())
+}
+
+/// Re-encrypt the vault under `new_passphrase`, with a new salt
+fn rotate(path: &Path, passphrase: &str, new_passphrase: &str) -> Result<VaultKey> {
+    validate_passphrase(new_passphrase)?;
+    let (_, secrets) = unlock(path, passphrase)?;
+
+    let key = KdfParams::generate().derive(new_passphrase)?;
+    key.save(path, &secrets)?;
+    log::info!("Rotated the key of secrets vault {}", path.display());
+    Ok(key)
+}
+
+/// Names as `${VAR}` references accept them
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(Error::Invalid(format!(
+            "Invalid secret name \"{}\": use letters, digits and underscores",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(Error::Invalid(format!(
+            "The passphrase must have at least {} characters",
+            MIN_PASSPHRASE_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Key of the vault while the desktop app has it unlocked
+#[derive(Default)]
+pub struct VaultState(Mutex<Option<VaultKey>>);
+
+impl VaultState {
+    /// Secrets for `${VAR}`, none while locked
+    pub fn secrets(&self, home: &Path) -> EnvVars {
+        let result = self.with_key(|key| {
+            let secrets = key.load(&vault_path(home))?;
+            Ok(secrets.0.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        });
+        match result {
+            Ok(secrets) => secrets,
+            Err(Error::VaultLocked) => EnvVars::new(),
+            Err(e) => {
+                log::warn!("Cannot read secrets vault: {}", e);
+                EnvVars::new()
+            }
+        }
+    }
+
+    /// Run `f` with the session's key, locking the vault if it was rotated
+    /// elsewhere
+    fn with_key<T>(&self, f: impl FnOnce(&VaultKey) -> Result<T>) -> Result<T> {
+        let mut key = self.0.lock().unwrap();
+        let result = f(key.as_ref().ok_or(Error::VaultLocked)?);
+        if matches!(result, Err(Error::VaultLocked | Error::NoVault(_))) {
+            *key = None;
+        }
+        result
+    }
+
+    fn set_key(&self, key: Option<VaultKey>) {
+        *self.0.lock().unwrap() = key;
+    }
+}
+
+/// Whether a vault exists and is unlocked
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub exists: bool,
+    pub unlocked: bool,
+}
+
+#[tauri::command]
+pub fn vault_status(app: tauri::AppHandle) -> Result<VaultStatus> {
+    let path = vault_path(&app.path().home_dir()?);
+    let unlocked = app.state::<VaultState>().with_key(|key| key.load(&path)).is_ok();
+    Ok(VaultStatus {
+        exists: path.exists(),
+        unlocked,
+    })
+}
+
+/// Unlock the vault for the session, creating it on first use
+#[tauri::command]
+pub fn vault_unlock(app: tauri::AppHandle, passphrase: String) -> Result<()> {
+    let passphrase = Zeroizing::new(passphrase);
+    let path = vault_path(&app.path().home_dir()?);
+    let key = if path.exists() {
+        unlock(&path, &passphrase)?.0
+    } else {
+        create(&path, &passphrase)?
+    };
+    app.state::<VaultState>().set_key(Some(key));
+    log::info!("Secrets vault unlocked");
+    Ok(())
+}
+
+#[tauri::command]
+pub fn vault_lock(app: tauri::AppHandle) {
+    app.state::<VaultState>().set_key(None);
+    log::info!("Secrets vault locked");
+}
+
+/// Names of the stored secrets, never their values
+#[tauri::command]
+pub fn vault_list(app: tauri::AppHandle) -> Result<Vec<String>> {
+    let path = vault_path(&app.path().home_dir()?);
+    app.state::<VaultState>()
+        .with_key(|key| Ok(key.load(&path)?.0.keys().cloned().collect()))
+}
+
+#[tauri::command]
+pub fn vault_set(app: tauri::AppHandle, name: String, value: String) -> Result<()> {
+    let value = Zeroizing::new(value);
+    let path = vault_path(&app.path().home_dir()?);
+    app.state::<VaultState>()
+        .with_key(|key| set(key, &path, name.trim(), &value))
+}
+
+#[tauri::command]
+pub fn vault_remove(app: tauri::AppHandle, name: String) -> Result<()> {
+    let path = vault_path(&app.path().home_dir()?);
+    app.state::<VaultState>().with_key(|key| remove(key, &path, &name))
+}
+
+/// Re-encrypt under a new passphrase; the current one is asked for again
+#[tauri::command]
+pub fn vault_rotate(app: tauri::AppHandle, passphrase: String, new_passphrase: String) -> Result<()> {
+    let (passphrase, new_passphrase) = (Zeroizing::new(passphrase), Zeroizing::new(new_passphrase));
+    let path = vault_path(&app.path().home_dir()?);
+    let key = rotate(&path, &passphrase, &new_passphrase)?;
+    app.state::<VaultState>().set_key(Some(key));
+    Ok(())
+}
+
+/// Run `claude-config-desktop secrets <args>`
+pub fn run_cli(args: &[String]) -> Result<()> {
+    let home = std::env::home_dir().ok_or_else(|| Error::Invalid("No home directory".to_string()))?;
+    run_command(&vault_path(&home), args, &passphrase)
+}
+
+/// Run a `secrets` subcommand on the vault at `path`, asking `passphrase`
+/// for the current passphrase
+fn run_command(
+    path: &Path,
+    args: &[String],
+    passphrase: &dyn Fn() -> Result<Zeroizing<String>>,
+) -> Result<()> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        ["list"] => {
+            let (_, secrets) = unlock(path, &passphrase()?)?;
+            for name in secrets.0.keys() {
+                println!("{}", name);
+            }
+        }
+        ["set", name] => {
+            validate_name(name)?;
+            let key = if path.exists() {
+                unlock(path, &passphrase()?)?.0
+            } else {
+                eprintln!("Creating a secrets vault at {}", path.display());
+                create(path, &new_passphrase()?)?
+            };
+            set(&key, path, name, &prompt(&format!("Value of {}: ", name))?)?;
+        }
+        ["remove", name] => {
+            let (key, _) = unlock(path, &passphrase()?)?;
+            remove(&key, path, name)?;
+        }
+        ["rotate"] => {
+            let current = passphrase()?;
+            rotate(path, &current, &new_passphrase()?)?;
+            eprintln!("Vault re-encrypted; unlock it again in the app");
+        }
+        _ => return Err(Error::Invalid(USAGE.to_string())),
+    }
+    Ok(())
+}
+
+/// Passphrase from the environment, or else asked for
+fn passphrase() -> Result<Zeroizing<String>> {
+    match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(Zeroizing::new(passphrase)),
+        _ => prompt("Vault passphrase: "),
+    }
+}
+
+fn new_passphrase() -> Result<Zeroizing<String>> {
+    let passphrase = prompt("New vault passphrase: ")?;
+    validate_passphrase(&passphrase)?;
+    if std::io::stdin().is_terminal() && *prompt("Repeat the passphrase: ")? != *passphrase {
+        return Err(Error::Invalid("The passphrases do not match".to_string()));
+    }
+    Ok(passphrase)
+}
+
+/// Read a line without echo on a terminal, or from piped stdin
+fn prompt(label: &str) -> Result<Zeroizing<String>> {
+    if std::io::stdin().is_terminal() {
+        return Ok(Zeroizing::new(rpassword::prompt_password(label)?));
+    }
+    let mut line = Zeroizing::new(String::new());
+    std::io::stdin().lock().read_line(&mut line)?;
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse";
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    fn passphrase() -> Result<Zeroizing<String>> {
+        Ok(Zeroizing::new(PASSPHRASE.to_string()))
+    }
+
+    #[test]
+    fn secrets_survive_a_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        let key = create(&path, PASSPHRASE).unwrap();
+        set(&key, &path, "GITHUB_TOKEN", "ghp_123").unwrap();
+
+        let (_, secrets) = unlock(&path, PASSPHRASE).unwrap();
+        assert_eq!(secrets.0.get("GITHUB_TOKEN").map(String::as_str), Some("ghp_123"));
+        // Only the ciphertext is written
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("ghp_123"));
+        assert!(create(&path, PASSPHRASE).is_err());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        create(&path, PASSPHRASE).unwrap();
+        assert!(matches!(unlock(&path, "battery staple"), Err(Error::WrongPassphrase)));
+    }
+
+    #[test]
+    fn corrupt_vault_is_not_a_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        create(&path, PASSPHRASE).unwrap();
+
+        let mut file = read(&path).unwrap();
+        file.nonce = "not base64!".to_string();
+        save_json(&path, &file).unwrap();
+        let Err(Error::Invalid(err)) = unlock(&path, PASSPHRASE) else {
+            panic!("a corrupt nonce was accepted");
+        };
+        assert!(err.starts_with("The vault is corrupt: nonce"), "{}", err);
+
+        file.nonce = BASE64.encode([0u8; 4]);
+        save_json(&path, &file).unwrap();
+        assert!(matches!(unlock(&path, PASSPHRASE), Err(Error::Invalid(_))));
+    }
+
+    #[test]
+    fn rotate_changes_salt_and_nonce_and_keeps_secrets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        let key = create(&path, PASSPHRASE).unwrap();
+        set(&key, &path, "GITHUB_TOKEN", "ghp_123").unwrap();
+        let before = read(&path).unwrap();
+
+        rotate(&path, PASSPHRASE, "battery staple").unwrap();
+        let after = read(&path).unwrap();
+        assert_ne!(after.kdf.salt, before.kdf.salt);
+        assert_ne!(after.nonce, before.nonce);
+        assert!(matches!(unlock(&path, PASSPHRASE), Err(Error::WrongPassphrase)));
+        let (_, secrets) = unlock(&path, "battery staple").unwrap();
+        assert_eq!(secrets.0.get("GITHUB_TOKEN").map(String::as_str), Some("ghp_123"));
+    }
+
+    #[test]
+    fn session_key_is_dropped_after_a_rotate_elsewhere() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        let state = VaultState::default();
+        state.set_key(Some(create(&path, PASSPHRASE).unwrap()));
+        state.with_key(|key| set(key, &path, "GITHUB_TOKEN", "ghp_123")).unwrap();
+
+        // As `secrets rotate` from a terminal would
+        rotate(&path, PASSPHRASE, "battery staple").unwrap();
+        assert!(matches!(state.with_key(|key| key.load(&path)), Err(Error::VaultLocked)));
+        assert!(state.0.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn names_must_be_variable_names() {
+        for name in ["GITHUB_TOKEN", "_private", "key2"] {
+            assert!(validate_name(name).is_ok(), "{}", name);
+        }
+        for name in ["", "2FA", "MY-TOKEN", "MY TOKEN", "${TOKEN}", "TÖKEN"] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn secrets_commands_are_dispatched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        let key = create(&path, PASSPHRASE).unwrap();
+        set(&key, &path, "GITHUB_TOKEN", "ghp_123").unwrap();
+        set(&key, &path, "NPM_TOKEN", "npm_456").unwrap();
+
+        run_command(&path, &args(&["list"]), &passphrase).unwrap();
+        run_command(&path, &args(&["remove", "NPM_TOKEN"]), &passphrase).unwrap();
+
+        let (_, secrets) = unlock(&path, PASSPHRASE).unwrap();
+        assert_eq!(secrets.0.keys().collect::<Vec<_>>(), ["GITHUB_TOKEN"]);
+    }
+
+    #[test]
+    fn unknown_secrets_commands_print_the_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(VAULT_FILE_NAME);
+        for command in [&[][..], &["get", "TOKEN"], &["set"], &["list", "extra"]] {
+            let err = run_command(&path, &args(command), &passphrase).unwrap_err();
+            assert!(matches!(err, Error::Invalid(usage) if usage == USAGE));
+        }
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,346 @@
+//! Native watcher for config folders
+//!
+//! Replaces polling `/api/file-hashes` in the desktop app: the active
+//! project's tool folders, the config part of `~/.claude`, the `mcps.json`
+//! hierarchy above the project and the registry are watched. Changes reach
+//! the webview as a debounced `config-changed` event and may trigger an
+//! auto-apply.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::auto_apply;
+use crate::cli::LaunchArgs;
+use crate::config;
+use crate::error::Result;
+use crate::preferences::Preferences;
+use crate::projects::ProjectsRegistry;
+use crate::util::is_atomic_tmp;
+
+/// Event sent to the webview after a burst of changes
+pub const CONFIG_CHANGED: &str = "config-changed";
+
+/// Quiet period that ends a burst of changes
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Tool folders watched in the project
+const PROJECT_FOLDERS: &[&str] = &[".claude", ".gemini", ".agent", ".codex"];
+
+/// Config files directly in `~/.claude`; the rest of it is session state that
+/// Claude Code rewrites constantly
+const HOME_FILES: &[&str] = &["mcps.json", "registry.json", "settings.json", ".env", "CLAUDE.md"];
+
+/// Config folders in `~/.claude`, watched recursively
+const HOME_FOLDERS: &[&str] = &["rules", "commands", "workflows"];
+
+/// Payload of [`CONFIG_CHANGED`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChanged {
+    pub dir: PathBuf,
+    pub paths: Vec<PathBuf>,
+}
+
+/// Watcher for the active project, replaced when the UI switches projects
+#[derive(Default)]
+pub struct ConfigWatcher {
+    active: Mutex<Option<ActiveWatch>>,
+}
+
+struct ActiveWatch {
+    dir: PathBuf,
+    home: PathBuf,
+    registry_path: PathBuf,
+    watcher: RecommendedWatcher,
+    watched: HashSet<PathBuf>,
+}
+
+impl ActiveWatch {
+    /// Paths to watch; folders may not exist yet, so their parents are
+    /// watched too and they are picked up once created
+    fn roots(&self) -> Vec<(PathBuf, RecursiveMode)> {
+        let home_claude = self.home.join(".claude");
+        let mut roots = vec![
+            (self.dir.clone(), RecursiveMode::NonRecursive),
+            (home_claude.clone(), RecursiveMode::NonRecursive),
+        ];
+        roots.extend(
+            PROJECT_FOLDERS
+                .iter()
+                .map(|folder| self.dir.join(folder))
+                .filter(|path| *path != home_claude)
+                .map(|path| (path, RecursiveMode::Recursive)),
+        );
+        roots.extend(
+            HOME_FOLDERS
+                .iter()
+                .map(|folder| (home_claude.join(folder), RecursiveMode::Recursive)),
+        );
+
+        // Parent configs merged into the project's
+        let project_claude = self.dir.join(".claude");
+        roots.extend(
+            config::find_all_configs(&self.dir, &self.home)
+                .into_iter()
+                .filter_map(|(_, config_path)| config_path.parent().map(Path::to_path_buf))
+                .filter(|folder| *folder != project_claude && *folder != home_claude)
+                .map(|folder| (folder, RecursiveMode::NonRecursive)),
+        );
+
+        if let Some(registry_dir) = self.registry_path.parent() {
+            roots.push((registry_dir.to_path_buf(), RecursiveMode::NonRecursive));
+        }
+        roots
+    }
+
+    /// Forget roots that were removed, then start watching roots that exist
+    /// and are not watched yet, so a folder deleted and created again is
+    /// watched again
+    fn sync_roots(&mut self) {
+        let roots = self.roots();
+        let stale: Vec<PathBuf> = self
+            .watched
+            .iter()
+            .filter(|path| !path.is_dir() || !roots.iter().any(|(root, _)| root == *path))
+            .cloned()
+            .collect();
+        for path in stale {
+            // The watch usually ended with the folder already
+            let _ = self.watcher.unwatch(&path);
+            self.watched.remove(&path);
+        }
+
+        for (path, mode) in roots {
+            if self.watched.contains(&path) || !path.is_dir() {
+                continue;
+            }
+            match self.watcher.watch(&path, mode) {
+                Ok(()) => {
+                    self.watched.insert(path);
+                }
+                Err(e) => log::warn!("Cannot watch {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// Whether a change under a watched root concerns config
+    fn is_relevant(&self, path: &Path) -> bool {
+        // The rename that completes an atomic write reports the real file
+        if is_atomic_tmp(path) {
+            return false;
+        }
+        if path == self.registry_path || self.is_parent_config(path) {
+            return true;
+        }
+
+        let home_claude = self.home.join(".claude");
+        if let Ok(rest) = path.strip_prefix(&home_claude) {
+            let Some(first) = rest.components().next() else {
+                return false;
+            };
+            let first = first.as_os_str();
+            return HOME_FILES.iter().any(|f| first == *f)
+                || HOME_FOLDERS.iter().any(|f| first == *f);
+        }
+
+        PROJECT_FOLDERS
+            .iter()
+            .any(|folder| path.starts_with(self.dir.join(folder)))
+    }
+
+    /// `.claude/mcps.json` or `.claude/.env` in a directory above the project
+    fn is_parent_config(&self, path: &Path) -> bool {
+        let Some(folder) = path.parent().filter(|f| f.ends_with(".claude")) else {
+            return false;
+        };
+        let is_config = matches!(
+            path.file_name().and_then(|n| n.to_str()),
+            Some("mcps.json" | ".env")
+        );
+        is_config && folder.parent().is_some_and(|d| self.dir.starts_with(d))
+    }
+}
+
+impl ConfigWatcher {
+    /// Watch `dir` instead of the previous project
+    pub fn watch(&self, app: &tauri::AppHandle, dir: PathBuf, home: PathBuf) -> Result<()> {
+        let mut active = self.active.lock().unwrap();
+        if active.as_ref().is_some_and(|a| a.dir == dir) {
+            return Ok(());
+        }
+
+        // Dropping the old watcher closes its channel and ends its thread
+        *active = None;
+
+        let (tx, rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            match result {
+                Ok(event) if !event.kind.is_access() => {
+                    let _ = tx.send(event.paths);
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Watch error: {}", e),
+            }
+        })?;
+
+        let registry_path = Preferences::load(&home).registry_path(&home);
+        let mut watch = ActiveWatch {
+            dir: dir.clone(),
+            home,
+            registry_path,
+            watcher,
+            watched: HashSet::new(),
+        };
+        watch.sync_roots();
+        *active = Some(watch);
+        log::info!("Watching config folders of {}", dir.display());
+
+        let app = app.clone();
+        std::thread::spawn(move || debounce(&app, &dir, rx));
+        Ok(())
+    }
+
+    /// Project being watched, i.e. the one the UI shows
+    pub fn dir(&self) -> Option<PathBuf> {
+        self.active.lock().unwrap().as_ref().map(|a| a.dir.clone())
+    }
+
+    /// Keep relevant paths of a burst and watch folders created by it
+    fn collect(&self, dir: &Path, paths: HashSet<PathBuf>) -> Option<Vec<PathBuf>> {
+        let mut active = self.active.lock().unwrap();
+        let watch = active.as_mut().filter(|a| a.dir == dir)?;
+        watch.sync_roots();
+
+        let mut relevant: Vec<PathBuf> = paths.into_iter().filter(|p| watch.is_relevant(p)).collect();
+        relevant.sort();
+        Some(relevant)
+    }
+}
+
+/// Group changes into bursts and emit one event per burst
+fn debounce(app: &tauri::AppHandle, dir: &Path, rx: Receiver<Vec<PathBuf>>) {
+    while let Ok(first) = rx.recv() {
+        let mut paths: HashSet<PathBuf> = first.into_iter().collect();
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(more) => paths.extend(more),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let Some(paths) = app.state::<ConfigWatcher>().collect(dir, paths) else {
+            return;
+        };
+        if paths.is_empty() {
+            continue;
+        }
+
+        log::debug!("Config changed: {:?}", paths);
+        let payload = ConfigChanged {
+            dir: dir.to_path_buf(),
+            paths: paths.clone(),
+        };
+        if let Err(e) = app.emit(CONFIG_CHANGED, payload) {
+            log::warn!("Failed to emit {}: {}", CONFIG_CHANGED, e);
+        }
+
+        auto_apply::on_config_changed(app, dir, &paths);
+    }
+}
+
+/// Watch the project from the command line, or else the active project from
+/// `projects.json`, so auto-apply works before the UI has loaded
+pub fn watch_active_project(app: &tauri::AppHandle) -> Result<()> {
+    let home = app.path().home_dir()?;
+    let dir = match app.state::<LaunchArgs>().dir.clone() {
+        Some(dir) => dir,
+        None => match ProjectsRegistry::load(&home).active() {
+            Some(project) => project.path.clone(),
+            None => return Ok(()),
+        },
+    };
+    app.state::<ConfigWatcher>().watch(app, dir, home)
+}
+
+/// Watch the config folders of the project the UI is showing
+#[tauri::command]
+pub fn watch_project(
+    app: tauri::AppHandle,
+    watcher: tauri::State<'_, ConfigWatcher>,
+    dir: String,
+) -> Result<()> {
+    let home = app.path().home_dir()?;
+    let dir = std::path::absolute(dir)?;
+    watcher.watch(&app, dir, home)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active_watch(dir: &Path, home: &Path) -> ActiveWatch {
+        ActiveWatch {
+            dir: dir.to_path_buf(),
+            home: home.to_path_buf(),
+            registry_path: home.join(".claude").join("registry.json"),
+            watcher: notify::recommended_watcher(|_: notify::Result<notify::Event>| {}).unwrap(),
+            watched: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn tool_folders_of_the_project_are_relevant() {
+        let watch = active_watch(Path::new("/work/app"), Path::new("/home/me"));
+        for folder in PROJECT_FOLDERS {
+            assert!(watch.is_relevant(&Path::new("/work/app").join(folder).join("mcps.json")));
+        }
+        assert!(watch.is_relevant(Path::new("/work/app/.codex/config.toml")));
+        assert!(!watch.is_relevant(Path::new("/work/app/src/main.rs")));
+    }
+
+    #[test]
+    fn atomic_write_temporaries_are_ignored() {
+        let watch = active_watch(Path::new("/work/app"), Path::new("/home/me"));
+        assert!(!watch.is_relevant(Path::new("/work/app/.claude/.mcps.json.4242.0.tmp")));
+        assert!(!watch.is_relevant(Path::new("/home/me/.claude/.registry.json.17.3.tmp")));
+        assert!(watch.is_relevant(Path::new("/home/me/.claude/registry.json")));
+    }
+
+    #[test]
+    fn only_write_atomic_names_count_as_temporaries() {
+        assert!(is_atomic_tmp(Path::new("/p/.settings.json.123.0.tmp")));
+        assert!(is_atomic_tmp(Path::new("/p/.env.123.45.tmp")));
+        assert!(!is_atomic_tmp(Path::new("/p/settings.json.123.0.tmp")));
+        assert!(!is_atomic_tmp(Path::new("/p/.notes.123.tmp")));
+        assert!(!is_atomic_tmp(Path::new("/p/.notes.tmp")));
+        assert!(!is_atomic_tmp(Path::new("/p/.notes.v2.tmp")));
+    }
+
+    #[test]
+    fn removed_folders_are_watched_again_once_recreated() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = tempfile::tempdir().unwrap();
+        let claude = dir.path().join(".claude");
+        std::fs::create_dir(&claude).unwrap();
+
+        let mut watch = active_watch(dir.path(), home.path());
+        watch.sync_roots();
+        assert!(watch.watched.contains(&claude));
+
+        std::fs::remove_dir(&claude).unwrap();
+        watch.sync_roots();
+        assert!(!watch.watched.contains(&claude));
+
+        std::fs::create_dir(&claude).unwrap();
+        watch.sync_roots();
+        assert!(watch.watched.contains(&claude));
+    }
+}
@@ -0,0 +1,66 @@
+//! Workstreams
+//!
+//! Reads and switches the active workstream in `workstreams.json`, like
+//! `loadWorkstreams` and `workstreamUse` in `lib/workstreams.js`. Fields the
+//! host does not use are kept as they are.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::projects::install_dir;
+use crate::util::{load_json, save_json};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workstreams {
+    #[serde(default)]
+    pub workstreams: Vec<Workstream>,
+    #[serde(default)]
+    pub active_id: Option<String>,
+    #[serde(flatten)]
+    rest: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workstream {
+    pub id: String,
+    pub name: String,
+    #[serde(flatten)]
+    rest: Map<String, Value>,
+}
+
+pub fn path(home: &Path) -> PathBuf {
+    install_dir(home).join("workstreams.json")
+}
+
+impl Workstreams {
+    /// Load the workstreams, treating a missing or invalid file as empty
+    pub fn load(home: &Path) -> Self {
+        load_json(&path(home))
+            .unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                None
+            })
+            .unwrap_or_default()
+    }
+
+    /// Make the workstream with this id or name active
+    pub fn activate(home: &Path, id_or_name: &str) -> Result<Workstream> {
+        let path = path(home);
+        let mut data: Self = load_json(&path)?.unwrap_or_default();
+        let workstream = data
+            .workstreams
+            .iter()
+            .find(|w| w.id == id_or_name || w.name.eq_ignore_ascii_case(id_or_name))
+            .cloned()
+            .ok_or_else(|| Error::Invalid(format!("Workstream not found: {}", id_or_name)))?;
+
+        data.active_id = Some(workstream.id.clone());
+        save_json(&path, &data)?;
+        log::info!("Switched to workstream: {}", workstream.name);
+        Ok(workstream)
+    }
+}
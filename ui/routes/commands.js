@@ -2,8 +2,8 @@
  * Commands Routes
  */
 
-const fs = require('fs');
 const path = require('path');
+const files = require('./files');
 
 /**
  * Get all commands
@@ -16,9 +16,9 @@ function getCommands(manager, projectDir) {
 /**
  * Get a single command
  */
-function getCommand(fullPath) {
+async function getCommand(fullPath) {
   try {
-    return { content: fs.readFileSync(fullPath, 'utf8') };
+    return { content: await files.read(fullPath) };
   } catch (e) {
     return { error: e.message };
   }
@@ -27,9 +27,9 @@ function getCommand(fullPath) {
 /**
  * Save a command
  */
-function saveCommand(body) {
+async function saveCommand(body) {
   try {
-    fs.writeFileSync(body.path, body.content);
+    await files.write(body.path, body.content);
     return { success: true };
   } catch (e) {
     return { error: e.message };
@@ -39,14 +39,11 @@ function saveCommand(body) {
 /**
  * Create a command
  */
-function createCommand(body, projectDir) {
+async function createCommand(body, projectDir) {
   try {
     const dir = body.dir || path.join(projectDir, '.claude', 'commands');
-    if (!fs.existsSync(dir)) {
-      fs.mkdirSync(dir, { recursive: true });
-    }
     const filePath = path.join(dir, body.name);
-    fs.writeFileSync(filePath, body.content || '');
+    await files.write(filePath, body.content || '');
     return { success: true, path: filePath };
   } catch (e) {
     return { error: e.message };
@@ -56,9 +53,14 @@ function createCommand(body, projectDir) {
 /**
  * Delete a command
  */
-function deleteCommand(fullPath) {
+async function deleteCommand(fullPath) {
   try {
-    fs.unlinkSync(fullPath);
+    // Only single files, unlike the file explorer's delete
+    const stat = await files.stat(fullPath);
+    if (!stat || stat.isDirectory) {
+      return { error: `Not a command file: ${fullPath}` };
+    }
+    await files.remove(fullPath);
     return { success: true };
   } catch (e) {
     return { error: e.message };
@@ -0,0 +1,83 @@
+/**
+ * Desktop Auth - per-launch token for the server run by the desktop app
+ *
+ * The desktop app generates a secret at launch and passes it in
+ * CLAUDE_CONFIG_TOKEN. Its webview opens the UI once with the secret in the
+ * `token` query parameter and gets it back as an HttpOnly cookie, which every
+ * later request, WebSocket and window of the app then carries. The host's
+ * own requests, and those its app:// proxy forwards, send it in the
+ * X-Claude-Config-Token header (see src-tauri/src/proxy.rs). Anything else
+ * is rejected. The token is never handed to page scripts.
+ *
+ * `claude-config ui` sets no token and stays open as before.
+ */
+
+const crypto = require('crypto');
+
+const ENV = 'CLAUDE_CONFIG_TOKEN';
+const COOKIE = 'claude_config_token';
+const HEADER = 'x-claude-config-token';
+
+// Read once and hidden from the shells and tools the server spawns
+const token = process.env[ENV] || null;
+delete process.env[ENV];
+
+function matches(value) {
+  if (!token || typeof value !== 'string') return false;
+  const a = Buffer.from(value);
+  const b = Buffer.from(token);
+  return a.length === b.length && crypto.timingSafeEqual(a, b);
+}
+
+function getCookie(req, name) {
+  for (const part of (req.headers.cookie || '').split(';')) {
+    const [key, ...value] = part.trim().split('=');
+    if (key === name) return value.join('=');
+  }
+  return null;
+}
+
+/**
+ * Whether a request carries the token
+ */
+function isAuthorized(req) {
+  if (!token) return true;
+  return matches(req.headers[HEADER]) || matches(getCookie(req, COOKIE));
+}
+
+/**
+ * Check a request, answering it if it must go no further
+ *
+ * A valid `token` query parameter sets the cookie and redirects to the same
+ * URL without it. Returns true when the request may be handled.
+ */
+function authorize(req, res, parsedUrl) {
+  if (!token) return true;
+
+  const { token: queryToken, ...query } = parsedUrl.query;
+  if (queryToken !== undefined) {
+    if (!matches(queryToken)) return reject(res);
+
+    const search = new URLSearchParams(query).toString();
+    res.writeHead(302, {
+      'Set-Cookie': `${COOKIE}=${token}; Path=/; HttpOnly; SameSite=Strict`,
+      'Location': parsedUrl.pathname + (search ? `?${search}` : ''),
+    });
+    res.end();
+    return false;
+  }
+
+  if (isAuthorized(req)) return true;
+  return reject(res);
+}
+
+function reject(res) {
+  res.writeHead(401, { 'Content-Type': 'application/json' });
+  res.end(JSON.stringify({ error: 'Unauthorized' }));
+  return false;
+}
+
+module.exports = {
+  isAuthorized,
+  authorize,
+};
@@ -0,0 +1,99 @@
+/**
+ * Desktop Bridge - requests from the server to the desktop app host
+ *
+ * When the desktop app runs the server it sets CLAUDE_CONFIG_DESKTOP to its
+ * version. Requests are written to stdout as one line starting with PREFIX,
+ * and the host answers on stdin with a JSON line carrying the same id
+ * (see src-tauri/src/bridge.rs).
+ */
+
+const readline = require('readline');
+
+const PREFIX = '@desktop ';
+
+// Update checks go over the network
+const TIMEOUT_MS = 60000;
+
+const pending = new Map();
+let nextId = 1;
+let listening = false;
+
+/**
+ * Whether the server runs inside the desktop app
+ */
+function isDesktop() {
+  return !!process.env.CLAUDE_CONFIG_DESKTOP;
+}
+
+/**
+ * Version of the desktop app running the server
+ */
+function desktopVersion() {
+  return process.env.CLAUDE_CONFIG_DESKTOP || null;
+}
+
+function listen() {
+  if (listening) return;
+  listening = true;
+
+  const rl = readline.createInterface({ input: process.stdin });
+  rl.on('line', (line) => {
+    let response;
+    try {
+      response = JSON.parse(line);
+    } catch (e) {
+      return;
+    }
+    const request = pending.get(response.id);
+    if (!request) return;
+    pending.delete(response.id);
+    if (response.error) {
+      request.reject(new Error(response.error));
+    } else {
+      request.resolve(response.result);
+    }
+  });
+}
+
+/**
+ * Send a request to the desktop host
+ */
+function request(method, params = {}) {
+  if (!isDesktop()) {
+    return Promise.reject(new Error('Not running in the desktop app'));
+  }
+  listen();
+
+  const id = nextId++;
+  return new Promise((resolve, reject) => {
+    const timer = setTimeout(() => {
+      pending.delete(id);
+      reject(new Error(`Desktop app did not answer ${method}`));
+    }, TIMEOUT_MS);
+
+    pending.set(id, {
+      resolve: (result) => { clearTimeout(timer); resolve(result); },
+      reject: (error) => { clearTimeout(timer); reject(error); }
+    });
+    process.stdout.write(PREFIX + JSON.stringify({ id, method, params }) + '\n');
+  });
+}
+
+/**
+ * Version handshake: log when the server is not the one bundled with the app
+ */
+function checkVersion(serverVersion) {
+  const appVersion = desktopVersion();
+  if (appVersion && appVersion !== serverVersion) {
+    console.error(`[desktop] app is version ${appVersion} but server is version ${serverVersion}`);
+    return false;
+  }
+  return true;
+}
+
+module.exports = {
+  isDesktop,
+  desktopVersion,
+  request,
+  checkVersion,
+};
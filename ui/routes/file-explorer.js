@@ -5,6 +5,18 @@
 const fs = require('fs');
 const path = require('path');
 const os = require('os');
+const files = require('./files');
+
+/**
+ * Parse JSON content, treating invalid JSON as empty like loadJson
+ */
+function parseJson(content) {
+  try {
+    return JSON.parse(content) || {};
+  } catch (e) {
+    return {};
+  }
+}
 
 /**
  * Scan a directory for .claude, .agent, .gemini folders
@@ -258,17 +270,24 @@ function getIntermediatePaths(projectDir) {
 /**
  * Get contents of a specific .claude file
  */
-function getClaudeFile(filePath) {
-  if (!filePath || !fs.existsSync(filePath)) {
+async function getClaudeFile(filePath) {
+  if (!filePath) {
     return { error: 'File not found', path: filePath };
   }
 
-  const stat = fs.statSync(filePath);
-  if (stat.isDirectory()) {
-    return { error: 'Path is a directory', path: filePath };
+  let content;
+  try {
+    const stat = await files.stat(filePath);
+    if (!stat) {
+      return { error: 'File not found', path: filePath };
+    }
+    if (stat.isDirectory) {
+      return { error: 'Path is a directory', path: filePath };
+    }
+    content = await files.read(filePath);
+  } catch (e) {
+    return { error: e.message, path: filePath };
   }
-
-  const content = fs.readFileSync(filePath, 'utf8');
   const ext = path.extname(filePath);
 
   if (ext === '.json') {
@@ -285,43 +304,43 @@ function getClaudeFile(filePath) {
 /**
  * Save content to a .claude file
  */
-function saveClaudeFile(body) {
+async function saveClaudeFile(body) {
   const { path: filePath, content } = body;
   if (!filePath) {
     return { error: 'Path is required' };
   }
 
-  const dir = path.dirname(filePath);
-  if (!fs.existsSync(dir)) {
-    fs.mkdirSync(dir, { recursive: true });
+  try {
+    await files.write(filePath, content);
+  } catch (e) {
+    return { error: e.message, path: filePath };
   }
-
-  fs.writeFileSync(filePath, content, 'utf8');
   return { success: true, path: filePath };
 }
 
 /**
  * Delete a .claude file or folder
  */
-function deleteClaudeFile(filePath) {
-  if (!filePath || !fs.existsSync(filePath)) {
+async function deleteClaudeFile(filePath) {
+  if (!filePath) {
     return { error: 'File not found', path: filePath };
   }
 
-  const stat = fs.statSync(filePath);
-  if (stat.isDirectory()) {
-    fs.rmSync(filePath, { recursive: true });
-  } else {
-    fs.unlinkSync(filePath);
+  try {
+    if (!(await files.stat(filePath))) {
+      return { error: 'File not found', path: filePath };
+    }
+    await files.remove(filePath);
+  } catch (e) {
+    return { error: e.message, path: filePath };
   }
-
   return { success: true, path: filePath };
 }
 
 /**
  * Create a new .claude file
  */
-function createClaudeFile(body) {
+async function createClaudeFile(body) {
   const { dir, name, type, content = '' } = body;
   if (!dir || !name) {
     return { error: 'Dir and name are required' };
@@ -358,66 +377,68 @@ function createClaudeFile(body) {
       filePath = path.join(dir, '.claude', name);
   }
 
-  const parentDir = path.dirname(filePath);
-  if (!fs.existsSync(parentDir)) {
-    fs.mkdirSync(parentDir, { recursive: true });
-  }
-
-  if (fs.existsSync(filePath)) {
-    return { error: 'File already exists', path: filePath };
+  try {
+    if (await files.stat(filePath)) {
+      return { error: 'File already exists', path: filePath };
+    }
+    await files.write(filePath, initialContent);
+  } catch (e) {
+    return { error: e.message, path: filePath };
   }
-
-  fs.writeFileSync(filePath, initialContent, 'utf8');
   return { success: true, path: filePath, content: initialContent };
 }
 
 /**
  * Rename a .claude file
  */
-function renameClaudeFile(body) {
+async function renameClaudeFile(body) {
   const { oldPath, newName } = body;
   if (!oldPath || !newName) {
     return { error: 'oldPath and newName are required' };
   }
 
-  if (!fs.existsSync(oldPath)) {
-    return { error: 'File not found', path: oldPath };
-  }
-
   const dir = path.dirname(oldPath);
   const newPath = path.join(dir, newName.endsWith('.md') ? newName : `${newName}.md`);
 
-  if (fs.existsSync(newPath)) {
-    return { error: 'A file with that name already exists', path: newPath };
+  try {
+    if (!(await files.stat(oldPath))) {
+      return { error: 'File not found', path: oldPath };
+    }
+    if (await files.stat(newPath)) {
+      return { error: 'A file with that name already exists', path: newPath };
+    }
+    await files.rename(oldPath, newPath);
+  } catch (e) {
+    return { error: e.message, path: oldPath };
   }
-
-  fs.renameSync(oldPath, newPath);
   return { success: true, oldPath, newPath };
 }
 
 /**
  * Initialize a .claude folder in a directory
  */
-function initClaudeFolder(dir) {
+async function initClaudeFolder(dir) {
   if (!dir) {
     return { error: 'dir is required' };
   }
 
   const absDir = path.resolve(dir.replace(/^~/, os.homedir()));
-  if (!fs.existsSync(absDir)) {
-    return { error: 'Directory not found', dir: absDir };
-  }
-
   const claudeDir = path.join(absDir, '.claude');
-  if (fs.existsSync(claudeDir)) {
-    return { error: '.claude folder already exists', dir: claudeDir };
-  }
 
-  fs.mkdirSync(claudeDir, { recursive: true });
-  fs.writeFileSync(
-    path.join(claudeDir, 'mcps.json'),
-    JSON.stringify({ mcpServers: {} }, null, 2)
-  );
+  try {
+    if (!(await files.stat(absDir))) {
+      return { error: 'Directory not found', dir: absDir };
+    }
+    if (await files.stat(claudeDir)) {
+      return { error: '.claude folder already exists', dir: claudeDir };
+    }
+    await files.write(
+      path.join(claudeDir, 'mcps.json'),
+      JSON.stringify({ mcpServers: {} }, null, 2)
+    );
+  } catch (e) {
+    return { error: e.message, dir: claudeDir };
+  }
 
   return { success: true, dir: claudeDir };
 }
@@ -425,7 +446,7 @@ function initClaudeFolder(dir) {
 /**
  * Delete a .claude folder
  */
-function deleteClaudeFolder(dir) {
+async function deleteClaudeFolder(dir) {
   if (!dir) {
     return { error: 'dir is required' };
   }
@@ -433,18 +454,21 @@ function deleteClaudeFolder(dir) {
   const absDir = path.resolve(dir.replace(/^~/, os.homedir()));
   const claudeDir = path.join(absDir, '.claude');
 
-  if (!fs.existsSync(claudeDir)) {
-    return { error: '.claude folder not found', dir: claudeDir };
+  try {
+    if (!(await files.stat(claudeDir))) {
+      return { error: '.claude folder not found', dir: claudeDir };
+    }
+    await files.remove(claudeDir);
+  } catch (e) {
+    return { error: e.message, dir: claudeDir };
   }
-
-  fs.rmSync(claudeDir, { recursive: true, force: true });
   return { success: true, dir: claudeDir };
 }
 
 /**
  * Initialize .claude folders in batch
  */
-function initClaudeFolderBatch(dirs) {
+async function initClaudeFolderBatch(dirs) {
   if (!dirs || !Array.isArray(dirs) || dirs.length === 0) {
     return { error: 'dirs array is required' };
   }
@@ -453,7 +477,7 @@ function initClaudeFolderBatch(dirs) {
   let successCount = 0;
 
   for (const dir of dirs) {
-    const result = initClaudeFolder(dir);
+    const result = await initClaudeFolder(dir);
     results.push({ dir, ...result });
     if (result.success) {
       successCount++;
@@ -470,13 +494,19 @@ function initClaudeFolderBatch(dirs) {
 /**
  * Move or copy a .claude file/folder
  */
-function moveClaudeItem(body, manager) {
+async function moveClaudeItem(body) {
   const { sourcePath, targetDir, mode = 'copy', merge = false } = body;
   if (!sourcePath || !targetDir) {
     return { error: 'sourcePath and targetDir are required' };
   }
 
-  if (!fs.existsSync(sourcePath)) {
+  let sourceStat;
+  try {
+    sourceStat = await files.stat(sourcePath);
+  } catch (e) {
+    return { error: e.message, path: sourcePath };
+  }
+  if (!sourceStat) {
     return { error: 'Source not found', path: sourcePath };
   }
 
@@ -497,21 +527,16 @@ function moveClaudeItem(body, manager) {
     targetPath = path.join(targetClaudeDir, sourceName);
   }
 
-  // Ensure target directory exists
-  const targetParent = path.dirname(targetPath);
-  if (!fs.existsSync(targetParent)) {
-    fs.mkdirSync(targetParent, { recursive: true });
-  }
-
-  // Handle existing target
-  if (fs.existsSync(targetPath)) {
-    if (!merge) {
+  try {
+    // Handle existing target
+    const targetExists = !!(await files.stat(targetPath));
+    if (targetExists && !merge) {
       return { error: 'Target already exists', targetPath, needsMerge: true };
     }
 
-    if (targetPath.endsWith('.json')) {
-      const sourceContent = manager.loadJson(sourcePath) || {};
-      const targetContent = manager.loadJson(targetPath) || {};
+    if (targetExists && targetPath.endsWith('.json')) {
+      const sourceContent = parseJson(await files.read(sourcePath));
+      const targetContent = parseJson(await files.read(targetPath));
 
       if (sourceName === 'mcps.json') {
         const merged = {
@@ -520,20 +545,20 @@ function moveClaudeItem(body, manager) {
           include: [...new Set([...(targetContent.include || []), ...(sourceContent.include || [])])],
           mcpServers: { ...(targetContent.mcpServers || {}), ...(sourceContent.mcpServers || {}) }
         };
-        fs.writeFileSync(targetPath, JSON.stringify(merged, null, 2));
+        await files.write(targetPath, JSON.stringify(merged, null, 2));
       } else {
         const merged = { ...targetContent, ...sourceContent };
-        fs.writeFileSync(targetPath, JSON.stringify(merged, null, 2));
+        await files.write(targetPath, JSON.stringify(merged, null, 2));
       }
     } else {
-      fs.copyFileSync(sourcePath, targetPath);
+      await files.copy(sourcePath, targetPath);
     }
-  } else {
-    fs.copyFileSync(sourcePath, targetPath);
-  }
 
-  if (mode === 'move') {
-    fs.unlinkSync(sourcePath);
+    if (mode === 'move') {
+      await files.remove(sourcePath);
+    }
+  } catch (e) {
+    return { error: e.message, sourcePath, targetPath };
   }
 
   return { success: true, sourcePath, targetPath, mode };
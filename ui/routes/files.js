@@ -0,0 +1,65 @@
+/**
+ * Files - shared by the routes that take paths from the client
+ */
+
+const fs = require('fs');
+const path = require('path');
+const desktopBridge = require('./desktop-bridge');
+
+/**
+ * Changes and reads of client-supplied paths. Inside the desktop app the host
+ * performs them, only within registered projects and tool folders, and
+ * audits what it refuses (see src-tauri/src/files.rs).
+ */
+const files = {
+  // { isDirectory, size }, or null if nothing exists at filePath
+  async stat(filePath) {
+    if (desktopBridge.isDesktop()) {
+      return desktopBridge.request('statFile', { path: filePath });
+    }
+    if (!fs.existsSync(filePath)) {
+      return null;
+    }
+    const stat = fs.statSync(filePath);
+    return { isDirectory: stat.isDirectory(), size: stat.size };
+  },
+
+  async read(filePath) {
+    if (desktopBridge.isDesktop()) {
+      return desktopBridge.request('readFile', { path: filePath });
+    }
+    return fs.readFileSync(filePath, 'utf8');
+  },
+
+  async write(filePath, content) {
+    if (desktopBridge.isDesktop()) {
+      return desktopBridge.request('writeFile', { path: filePath, content });
+    }
+    fs.mkdirSync(path.dirname(filePath), { recursive: true });
+    fs.writeFileSync(filePath, content, 'utf8');
+  },
+
+  async remove(filePath) {
+    if (desktopBridge.isDesktop()) {
+      return desktopBridge.request('deleteFile', { path: filePath });
+    }
+    fs.rmSync(filePath, { recursive: true, force: true });
+  },
+
+  async copy(source, target) {
+    if (desktopBridge.isDesktop()) {
+      return desktopBridge.request('copyFile', { source, target });
+    }
+    fs.mkdirSync(path.dirname(target), { recursive: true });
+    fs.copyFileSync(source, target);
+  },
+
+  async rename(source, target) {
+    if (desktopBridge.isDesktop()) {
+      return desktopBridge.request('renameFile', { source, target });
+    }
+    fs.renameSync(source, target);
+  },
+};
+
+module.exports = files;
@@ -19,6 +19,8 @@ const settings = require('./settings');
 const env = require('./env');
 const configs = require('./configs');
 const mcpDiscovery = require('./mcp-discovery');
+const desktopBridge = require('./desktop-bridge');
+const desktopAuth = require('./desktop-auth');
 
 module.exports = {
   projects,
@@ -38,4 +40,6 @@ module.exports = {
   env,
   configs,
   mcpDiscovery,
+  desktopBridge,
+  desktopAuth,
 };
@@ -7,6 +7,7 @@
 const { spawn } = require('child_process');
 const path = require('path');
 const os = require('os');
+const desktopBridge = require('./desktop-bridge');
 
 // Cache for discovered tools (serverName -> { tools, timestamp })
 const toolsCache = new Map();
@@ -178,7 +179,10 @@ async function getServerTools(manager, serverName) {
   try {
     let tools;
 
-    if (config.command) {
+    if (config.command && desktopBridge.isDesktop()) {
+      // The desktop app starts processes itself
+      tools = await desktopBridge.request('mcpServerTools', { name: serverName });
+    } else if (config.command) {
       // stdio server
       tools = await discoverStdioTools(serverName, config);
     } else if (config.url) {
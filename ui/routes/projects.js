@@ -6,6 +6,7 @@ const fs = require('fs');
 const path = require('path');
 const os = require('os');
 const { execFileSync } = require('child_process');
+const desktopBridge = require('./desktop-bridge');
 
 /**
  * Get all registered projects with status info
@@ -61,7 +62,7 @@ function getActiveProject(manager, projectDir, getHierarchy, getSubprojects) {
  * Add a project to the registry
  * @param {boolean} runClaudeInit - If true, run `claude /init` to create CLAUDE.md
  */
-function addProject(manager, projectPath, name, setProjectDir, runClaudeInit = false) {
+async function addProject(manager, projectPath, name, setProjectDir, runClaudeInit = false) {
   if (!manager) return { error: 'Manager not available' };
 
   const absPath = path.resolve(projectPath.replace(/^~/, os.homedir()));
@@ -85,11 +86,16 @@ function addProject(manager, projectPath, name, setProjectDir, runClaudeInit = f
   // Run claude /init if requested and CLAUDE.md doesn't exist
   if (runClaudeInit && !fs.existsSync(claudeMd)) {
     try {
-      execFileSync('claude', ['/init'], {
-        cwd: absPath,
-        stdio: 'pipe',
-        timeout: 30000
-      });
+      if (desktopBridge.isDesktop()) {
+        // The desktop app starts processes itself
+        await desktopBridge.request('claudeInit', { dir: absPath });
+      } else {
+        execFileSync('claude', ['/init'], {
+          cwd: absPath,
+          stdio: 'pipe',
+          timeout: 30000
+        });
+      }
       claudeInitRan = true;
     } catch (err) {
       // Claude Code not installed or init failed
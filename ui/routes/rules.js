@@ -2,8 +2,8 @@
  * Rules Routes
  */
 
-const fs = require('fs');
 const path = require('path');
+const files = require('./files');
 
 /**
  * Get all rules
@@ -16,9 +16,9 @@ function getRules(manager, projectDir) {
 /**
  * Get a single rule
  */
-function getRule(fullPath) {
+async function getRule(fullPath) {
   try {
-    return { content: fs.readFileSync(fullPath, 'utf8') };
+    return { content: await files.read(fullPath) };
   } catch (e) {
     return { error: e.message };
   }
@@ -27,9 +27,9 @@ function getRule(fullPath) {
 /**
  * Save a rule
  */
-function saveRule(body) {
+async function saveRule(body) {
   try {
-    fs.writeFileSync(body.path, body.content);
+    await files.write(body.path, body.content);
     return { success: true };
   } catch (e) {
     return { error: e.message };
@@ -39,14 +39,11 @@ function saveRule(body) {
 /**
  * Create a rule
  */
-function createRule(body, projectDir) {
+async function createRule(body, projectDir) {
   try {
     const dir = body.dir || path.join(projectDir, '.claude', 'rules');
-    if (!fs.existsSync(dir)) {
-      fs.mkdirSync(dir, { recursive: true });
-    }
     const filePath = path.join(dir, body.name);
-    fs.writeFileSync(filePath, body.content || '');
+    await files.write(filePath, body.content || '');
     return { success: true, path: filePath };
   } catch (e) {
     return { error: e.message };
@@ -56,9 +53,14 @@ function createRule(body, projectDir) {
 /**
  * Delete a rule
  */
-function deleteRule(fullPath) {
+async function deleteRule(fullPath) {
   try {
-    fs.unlinkSync(fullPath);
+    // Only single files, unlike the file explorer's delete
+    const stat = await files.stat(fullPath);
+    if (!stat || stat.isDirectory) {
+      return { error: `Not a rule file: ${fullPath}` };
+    }
+    await files.remove(fullPath);
     return { success: true };
   } catch (e) {
     return { error: e.message };
@@ -6,6 +6,7 @@ const fs = require('fs');
 const path = require('path');
 const https = require('https');
 const { execSync } = require('child_process');
+const desktopBridge = require('./desktop-bridge');
 
 /**
  * Get version from file (checks both config-loader.js and lib/constants.js)
@@ -121,10 +122,43 @@ function isNewerVersion(source, installed) {
   return false;
 }
 
+/**
+ * Check for desktop app updates through the host
+ */
+async function checkForDesktopUpdates(manager) {
+  try {
+    const result = await desktopBridge.request('checkForUpdates');
+    return {
+      updateAvailable: !!result.version,
+      installedVersion: result.currentVersion,
+      latestVersion: result.version || result.currentVersion,
+      sourceVersion: result.version || result.currentVersion, // legacy alias for v0.37.0 compatibility
+      updateMethod: 'desktop',
+      installDir: manager.installDir
+    };
+  } catch (error) {
+    console.log('[update-check] desktop app check failed:', error.message);
+    return {
+      updateAvailable: false,
+      installedVersion: desktopBridge.desktopVersion(),
+      latestVersion: desktopBridge.desktopVersion(),
+      sourceVersion: desktopBridge.desktopVersion(), // legacy alias for v0.37.0 compatibility
+      updateMethod: 'desktop',
+      installDir: manager.installDir,
+      error: error.message
+    };
+  }
+}
+
 /**
  * Check for updates
  */
 async function checkForUpdates(manager, dirname) {
+  // The desktop app updates itself; npm would leave a mixed installation
+  if (desktopBridge.isDesktop()) {
+    return checkForDesktopUpdates(manager);
+  }
+
   // Get current installed version
   const installedVersion = getVersionFromFile(
     path.join(dirname, '..', 'config-loader.js')
@@ -317,6 +351,23 @@ function performLocalUpdate(sourcePath, manager) {
 async function performUpdate(options, manager) {
   const { updateMethod, sourcePath, targetVersion } = options;
 
+  // The desktop app shows the release notes and installs the update itself
+  if (desktopBridge.isDesktop()) {
+    try {
+      const result = await desktopBridge.request('installUpdate');
+      return {
+        success: true,
+        updateMethod: 'desktop',
+        newVersion: result.version || result.currentVersion,
+        message: result.version
+          ? `Version ${result.version} is being offered by the desktop app.`
+          : 'The desktop app is up to date.'
+      };
+    } catch (error) {
+      return { success: false, error: error.message };
+    }
+  }
+
   if (updateMethod === 'npm') {
     return await performNpmUpdate(targetVersion);
   }
@@ -6,6 +6,7 @@
  */
 
 const http = require('http');
+const https = require('https');
 const fs = require('fs');
 const path = require('path');
 const url = require('url');
@@ -16,9 +17,34 @@ const TerminalServer = require('./terminal-server.cjs');
 // Route modules
 const routes = require('./routes');
 
+/**
+ * GET a text file over HTTPS, resolving to { success, content } or { success: false, error }
+ */
+function fetchText(fileUrl) {
+  return new Promise((resolve) => {
+    const req = https.get(fileUrl, { headers: { 'User-Agent': 'claude-config-ui/1.0' } }, (res) => {
+      if (res.statusCode !== 200) {
+        res.resume();
+        resolve({ success: false, error: `HTTP ${res.statusCode}` });
+        return;
+      }
+      let content = '';
+      res.setEncoding('utf8');
+      res.on('data', chunk => content += chunk);
+      res.on('end', () => resolve({ success: true, content }));
+    });
+    req.on('error', (e) => resolve({ success: false, error: e.message }));
+    req.setTimeout(10000, () => {
+      req.destroy();
+      resolve({ success: false, error: 'Request timeout' });
+    });
+  });
+}
+
 class ConfigUIServer {
-  constructor(port = 3333, projectDir = null, manager = null) {
+  constructor(port = 3333, projectDir = null, manager = null, options = {}) {
     this.port = port;
+    this.socketPath = options.socket || null;
     this.manager = manager;
     this.distDir = path.join(__dirname, 'dist');
     this.terminalServer = new TerminalServer();
@@ -97,7 +123,18 @@ class ConfigUIServer {
     }
   }
 
-  getChangelog() {
+  /**
+   * CHANGELOG.md of this install, or of the release tagged `v${version}`,
+   * which lists what an update to it brings
+   */
+  async getChangelog(version) {
+    if (version) {
+      if (!/^\d+\.\d+\.\d+(-[0-9A-Za-z.-]+)?$/.test(version)) {
+        return { success: false, error: 'Invalid version' };
+      }
+      return fetchText(`https://raw.githubusercontent.com/regression-io/claude-config/v${version}/CHANGELOG.md`);
+    }
+
     try {
       const changelogPath = path.join(__dirname, '..', 'CHANGELOG.md');
       if (fs.existsSync(changelogPath)) {
@@ -253,8 +290,15 @@ class ConfigUIServer {
 
   start() {
     const server = http.createServer((req, res) => this.handleRequest(req, res));
-    this.terminalServer.attach(server);
 
+    // Inside the desktop app, the server must come from the same release
+    routes.desktopBridge.checkVersion(this.serverVersion);
+
+    if (this.socketPath) {
+      return this.startOnSocket(server);
+    }
+
+    this.terminalServer.attach(server);
     server.listen(this.port, () => {
       console.log(`\n🚀 Claude Config UI running at http://localhost:${this.port}`);
       console.log(`📁 Project: ${this.projectDir}`);
@@ -262,6 +306,23 @@ class ConfigUIServer {
     });
   }
 
+  /**
+   * Desktop app: HTTP on a private socket behind the app's proxy; the app
+   * runs terminal shells itself, so nothing listens on a port
+   */
+  startOnSocket(server) {
+    if (process.platform !== 'win32' && fs.existsSync(this.socketPath)) {
+      fs.unlinkSync(this.socketPath);
+    }
+    // Socket only accessible to the user
+    const umask = process.umask(0o077);
+    server.listen(this.socketPath, () => {
+      process.umask(umask);
+      console.log(`\n🚀 Claude Config UI running on ${this.socketPath}`);
+      console.log(`📁 Project: ${this.projectDir}\n`);
+    });
+  }
+
   async handleRequest(req, res) {
     const parsedUrl = url.parse(req.url, true);
     const pathname = parsedUrl.pathname;
@@ -276,6 +337,9 @@ class ConfigUIServer {
       return;
     }
 
+    // Inside the desktop app, only its webview may use the server
+    if (!routes.desktopAuth.authorize(req, res, parsedUrl)) return;
+
     try {
       if (pathname.startsWith('/api/')) {
         return this.handleAPI(req, res, pathname, parsedUrl.query);
@@ -393,18 +457,30 @@ class ConfigUIServer {
           version: this.serverVersion,
           currentVersion: this.getPackageVersion(),
           startTime: this.serverStartTime,
+          desktopVersion: routes.desktopBridge.desktopVersion(),
           needsRestart: this.serverVersion !== this.getPackageVersion()
         });
 
       case '/api/changelog':
-        return this.json(res, this.getChangelog());
+        return this.json(res, await this.getChangelog(query.version));
 
       case '/api/restart':
         if (req.method === 'POST') {
           this.json(res, { success: true, message: 'Server restarting...' });
-          setTimeout(() => {
+          setTimeout(async () => {
+            if (routes.desktopBridge.isDesktop()) {
+              // The desktop app's supervisor owns this process and starts
+              // the new server once it has exited
+              try {
+                await routes.desktopBridge.request('restartServer');
+              } catch (e) {
+                console.error('Restart failed:', e.message);
+                return;
+              }
+              process.exit(0);
+            }
             const child = spawn(process.argv[0], process.argv.slice(1), {
-              detached: true, stdio: 'ignore', cwd: process.cwd(), env: process.env
+              detached: true, stdio: 'ignore', cwd: process.cwd()
             });
             child.unref();
             process.exit(0);
@@ -457,20 +533,20 @@ class ConfigUIServer {
         return this.json(res, routes.rules.getRules(this.manager, this.projectDir));
 
       case '/api/rule':
-        if (req.method === 'GET') return this.json(res, routes.rules.getRule(query.path));
-        if (req.method === 'PUT') return this.json(res, routes.rules.saveRule(body));
-        if (req.method === 'DELETE') return this.json(res, routes.rules.deleteRule(query.path));
-        if (req.method === 'POST') return this.json(res, routes.rules.createRule(body, this.projectDir));
+        if (req.method === 'GET') return this.json(res, await routes.rules.getRule(query.path));
+        if (req.method === 'PUT') return this.json(res, await routes.rules.saveRule(body));
+        if (req.method === 'DELETE') return this.json(res, await routes.rules.deleteRule(query.path));
+        if (req.method === 'POST') return this.json(res, await routes.rules.createRule(body, this.projectDir));
         break;
 
       case '/api/commands':
         return this.json(res, routes.commands.getCommands(this.manager, this.projectDir));
 
       case '/api/command':
-        if (req.method === 'GET') return this.json(res, routes.commands.getCommand(query.path));
-        if (req.method === 'PUT') return this.json(res, routes.commands.saveCommand(body));
-        if (req.method === 'DELETE') return this.json(res, routes.commands.deleteCommand(query.path));
-        if (req.method === 'POST') return this.json(res, routes.commands.createCommand(body, this.projectDir));
+        if (req.method === 'GET') return this.json(res, await routes.commands.getCommand(query.path));
+        if (req.method === 'PUT') return this.json(res, await routes.commands.saveCommand(body));
+        if (req.method === 'DELETE') return this.json(res, await routes.commands.deleteCommand(query.path));
+        if (req.method === 'POST') return this.json(res, await routes.commands.createCommand(body, this.projectDir));
         break;
 
       case '/api/apply':
@@ -531,30 +607,30 @@ class ConfigUIServer {
         return this.json(res, routes.fileExplorer.getIntermediatePaths(this.projectDir));
 
       case '/api/claude-file':
-        if (req.method === 'GET') return this.json(res, routes.fileExplorer.getClaudeFile(query.path));
-        if (req.method === 'PUT') return this.json(res, routes.fileExplorer.saveClaudeFile(body));
-        if (req.method === 'DELETE') return this.json(res, routes.fileExplorer.deleteClaudeFile(query.path));
-        if (req.method === 'POST') return this.json(res, routes.fileExplorer.createClaudeFile(body));
+        if (req.method === 'GET') return this.json(res, await routes.fileExplorer.getClaudeFile(query.path));
+        if (req.method === 'PUT') return this.json(res, await routes.fileExplorer.saveClaudeFile(body));
+        if (req.method === 'DELETE') return this.json(res, await routes.fileExplorer.deleteClaudeFile(query.path));
+        if (req.method === 'POST') return this.json(res, await routes.fileExplorer.createClaudeFile(body));
         break;
 
       case '/api/claude-move':
-        if (req.method === 'POST') return this.json(res, routes.fileExplorer.moveClaudeItem(body, this.manager));
+        if (req.method === 'POST') return this.json(res, await routes.fileExplorer.moveClaudeItem(body));
         break;
 
       case '/api/claude-rename':
-        if (req.method === 'POST') return this.json(res, routes.fileExplorer.renameClaudeFile(body));
+        if (req.method === 'POST') return this.json(res, await routes.fileExplorer.renameClaudeFile(body));
         break;
 
       case '/api/init-claude-folder':
-        if (req.method === 'POST') return this.json(res, routes.fileExplorer.initClaudeFolder(body.dir));
+        if (req.method === 'POST') return this.json(res, await routes.fileExplorer.initClaudeFolder(body.dir));
         break;
 
       case '/api/delete-claude-folder':
-        if (req.method === 'POST') return this.json(res, routes.fileExplorer.deleteClaudeFolder(body.dir));
+        if (req.method === 'POST') return this.json(res, await routes.fileExplorer.deleteClaudeFolder(body.dir));
         break;
 
       case '/api/init-claude-folder-batch':
-        if (req.method === 'POST') return this.json(res, routes.fileExplorer.initClaudeFolderBatch(body.dirs));
+        if (req.method === 'POST') return this.json(res, await routes.fileExplorer.initClaudeFolderBatch(body.dirs));
         break;
 
       case '/api/sync/preview':
@@ -623,7 +699,7 @@ class ConfigUIServer {
 
       case '/api/projects':
         if (req.method === 'GET') return this.json(res, routes.projects.getProjects(this.manager, this.projectDir));
-        if (req.method === 'POST') return this.json(res, routes.projects.addProject(this.manager, body.path, body.name, (p) => { this.projectDir = p; }, body.runClaudeInit));
+        if (req.method === 'POST') return this.json(res, await routes.projects.addProject(this.manager, body.path, body.name, (p) => { this.projectDir = p; }, body.runClaudeInit));
         break;
 
       case '/api/projects/active':
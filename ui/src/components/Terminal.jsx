@@ -3,10 +3,12 @@ import { Terminal as XTerm } from '@xterm/xterm';
 import { FitAddon } from '@xterm/addon-fit';
 import { WebLinksAddon } from '@xterm/addon-web-links';
 import '@xterm/xterm/css/xterm.css';
+import desktop, { isDesktop } from '@/lib/desktop';
 
 /**
  * Embedded terminal component using xterm.js
- * Connects to the server via WebSocket for PTY access
+ * In the desktop app the host runs the shell; in the browser the server's
+ * WebSocket does
  */
 export default function Terminal({
   cwd,
@@ -19,7 +21,8 @@ export default function Terminal({
   const terminalRef = useRef(null);
   const xtermRef = useRef(null);
   const fitAddonRef = useRef(null);
-  const wsRef = useRef(null);
+  // { send(msg), close() } for either transport
+  const connRef = useRef(null);
   const [connected, setConnected] = useState(false);
   const [sessionId, setSessionId] = useState(null);
 
@@ -78,35 +81,109 @@ export default function Terminal({
       term.focus();
     }, 0);
 
-    // Connect to WebSocket
-    connectWebSocket(term);
+    // Connect to the shell
+    if (isDesktop()) {
+      connectDesktop(term);
+    } else {
+      connectWebSocket(term);
+    }
 
     // Cleanup
     return () => {
-      if (wsRef.current) {
-        wsRef.current.close();
+      if (connRef.current) {
+        connRef.current.close();
       }
       term.dispose();
       xtermRef.current = null;
     };
   }, []);
 
+  // Shell started by the desktop host, talked to over commands and events
+  const connectDesktop = useCallback(async (term) => {
+    let id = null;
+    // Output can arrive before terminalOpen resolves with the id
+    const early = [];
+    const handleOutput = (payload) => {
+      if (id === null) early.push(payload);
+      else if (payload.id === id) term.write(payload.data);
+    };
+    const stops = await Promise.all([
+      desktop.onTerminalOutput(handleOutput),
+      desktop.onTerminalExit((payload) => {
+        if (payload.id !== id) return;
+        term.write('\r\n\x1b[33m[Process exited]\x1b[0m\r\n');
+        setConnected(false);
+        if (onExit) onExit(payload.exitCode, null);
+      }),
+    ]);
+    const unlisten = () => stops.forEach((stop) => stop());
+
+    try {
+      id = await desktop.terminalOpen(cwd || null, term.cols, term.rows);
+    } catch (error) {
+      unlisten();
+      term.write(`\r\n\x1b[31m[Error: ${error}]\x1b[0m\r\n`);
+      return;
+    }
+    if (xtermRef.current !== term) {
+      unlisten();
+      desktop.terminalClose(id);
+      return;
+    }
+    early.splice(0).forEach(handleOutput);
+
+    const send = (msg) => {
+      switch (msg.type) {
+        case 'input':
+          return desktop.terminalWrite(id, msg.data).catch(() => {});
+        case 'resize':
+          return desktop.terminalResize(id, msg.cols, msg.rows).catch(() => {});
+        case 'kill':
+          return desktop.terminalClose(id);
+      }
+    };
+    connRef.current = {
+      send,
+      close: () => {
+        unlisten();
+        desktop.terminalClose(id);
+      },
+    };
+    setConnected(true);
+    setSessionId(String(id));
+    if (onReady) onReady(String(id));
+
+    if (initialCommand) {
+      send({ type: 'input', data: initialCommand + '\r' });
+    }
+    term.onData((data) => send({ type: 'input', data }));
+    term.onResize(({ cols, rows }) => send({ type: 'resize', cols, rows }));
+  }, [cwd, initialCommand, onReady, onExit]);
+
   // Connect to WebSocket terminal server
   const connectWebSocket = useCallback((term) => {
     const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
     const wsHost = window.location.hostname;
-    // Use port 3333 for the API server (WebSocket is on the same server)
-    const wsPort = 3333;
+    // WebSocket is on the API server: same port as the page, except under the
+    // Vite dev server (5173) which only proxies /api to port 3333
+    const wsPort = window.location.port && window.location.port !== '5173' ? window.location.port : 3333;
 
-    const params = new URLSearchParams();
-    if (cwd) params.set('cwd', cwd);
-    if (initialCommand) params.set('cmd', initialCommand);
+    const url = new URL(`${protocol}//${wsHost}:${wsPort}/ws/terminal`);
+    if (cwd) url.searchParams.set('cwd', cwd);
+    if (initialCommand) url.searchParams.set('cmd', initialCommand);
 
-    const wsUrl = `${protocol}//${wsHost}:${wsPort}/ws/terminal?${params.toString()}`;
+    const wsUrl = url.toString();
 
-    console.log('Connecting to terminal WebSocket:', wsUrl);
+    console.log('Connecting to terminal WebSocket:', `${url.origin}${url.pathname}`);
     const ws = new WebSocket(wsUrl);
-    wsRef.current = ws;
+    connRef.current = {
+      send: (msg) => {
+        if (ws.readyState === WebSocket.OPEN) {
+          ws.send(JSON.stringify(msg));
+        }
+      },
+      close: () => ws.close(),
+    };
 
     ws.onopen = () => {
       console.log('Terminal WebSocket connected');
@@ -155,18 +232,10 @@ export default function Terminal({
     };
 
     // Send terminal input to server
-    term.onData((data) => {
-      if (ws.readyState === WebSocket.OPEN) {
-        ws.send(JSON.stringify({ type: 'input', data }));
-      }
-    });
+    term.onData((data) => connRef.current.send({ type: 'input', data }));
 
     // Handle resize
-    term.onResize(({ cols, rows }) => {
-      if (ws.readyState === WebSocket.OPEN) {
-        ws.send(JSON.stringify({ type: 'resize', cols, rows }));
-      }
-    });
+    term.onResize(({ cols, rows }) => connRef.current.send({ type: 'resize', cols, rows }));
   }, [cwd, initialCommand, onReady, onExit]);
 
   // Handle container resize
@@ -193,15 +262,15 @@ export default function Terminal({
 
   // Send command to terminal
   const sendCommand = useCallback((command) => {
-    if (wsRef.current && wsRef.current.readyState === WebSocket.OPEN) {
-      wsRef.current.send(JSON.stringify({ type: 'input', data: command + '\r' }));
+    if (connRef.current) {
+      connRef.current.send({ type: 'input', data: command + '\r' });
     }
   }, []);
 
   // Kill terminal process
   const kill = useCallback(() => {
-    if (wsRef.current && wsRef.current.readyState === WebSocket.OPEN) {
-      wsRef.current.send(JSON.stringify({ type: 'kill' }));
+    if (connRef.current) {
+      connRef.current.send({ type: 'kill' });
     }
   }, []);
 
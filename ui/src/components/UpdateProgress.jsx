@@ -0,0 +1,97 @@
+import React, { useEffect, useState } from 'react';
+import { CheckCircle2, Download, Loader2, X } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Progress } from '@/components/ui/progress';
+import desktop, { isDesktop } from '@/lib/desktop';
+
+function formatBytes(bytes) {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${Math.round(bytes / 1024)} KB`;
+  return `${bytes} B`;
+}
+
+/**
+ * Progress card for desktop app updates, driven by `update-status` events
+ */
+export function UpdateProgress() {
+  const [status, setStatus] = useState(null);
+
+  useEffect(() => {
+    if (!isDesktop()) return;
+
+    let unlisten = null;
+    let cancelled = false;
+    desktop.onUpdateStatus((payload) => {
+      // Finished states are shown by the native dialogs
+      if (['installed', 'cancelled', 'failed'].includes(payload.status)) {
+        setStatus(null);
+      } else {
+        setStatus(payload);
+      }
+    }).then((stop) => {
+      if (cancelled) stop();
+      else unlisten = stop;
+    }).catch(() => {});
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  if (!status) return null;
+
+  const downloading = status.status === 'downloading';
+  const pending = status.status === 'pending';
+  const percent = downloading && status.total
+    ? Math.min(100, Math.round((status.downloaded / status.total) * 100))
+    : null;
+
+  return (
+    <div className="fixed bottom-4 right-4 z-50 w-80 rounded-lg border border-border bg-card p-4 shadow-lg">
+      <div className="flex items-center justify-between mb-2">
+        <div className="flex items-center gap-2 text-sm font-medium text-foreground">
+          {downloading ? (
+            <Download className="w-4 h-4 text-primary" />
+          ) : pending ? (
+            <CheckCircle2 className="w-4 h-4 text-green-500" />
+          ) : (
+            <Loader2 className="w-4 h-4 text-primary animate-spin" />
+          )}
+          {downloading
+            ? `Downloading v${status.version}`
+            : pending
+              ? `v${status.version} ready`
+              : `Installing v${status.version}`}
+        </div>
+        {(downloading || pending) && (
+          <Button
+            variant="ghost"
+            size="sm"
+            className="h-6 w-6 p-0"
+            onClick={() => (pending ? setStatus(null) : desktop.cancelUpdate())}
+            title={pending ? 'Dismiss' : 'Cancel update'}
+          >
+            <X className="w-4 h-4" />
+          </Button>
+        )}
+      </div>
+      {downloading && (
+        <>
+          <Progress value={percent ?? 0} />
+          <p className="text-xs text-muted-foreground mt-2">
+            {formatBytes(status.downloaded)}
+            {status.total ? ` of ${formatBytes(status.total)} (${percent}%)` : ''}
+          </p>
+        </>
+      )}
+      {pending && (
+        <p className="text-xs text-muted-foreground">
+          The update will be installed when you quit the app.
+        </p>
+      )}
+    </div>
+  );
+}
+
+export default UpdateProgress;
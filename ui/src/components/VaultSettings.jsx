@@ -0,0 +1,198 @@
+import React, { useEffect, useState } from 'react';
+import { KeyRound, Loader2, Lock, Plus, RefreshCw, Trash2, Unlock } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { toast } from 'sonner';
+import desktop from '@/lib/desktop';
+
+/**
+ * Secrets vault of the desktop app: unlock it for the session, then manage
+ * the secrets `apply` resolves ${VAR} from. Values are write-only here.
+ */
+export default function VaultSettings() {
+  const [status, setStatus] = useState(null);
+  const [names, setNames] = useState([]);
+  const [passphrase, setPassphrase] = useState('');
+  const [secret, setSecret] = useState({ name: '', value: '' });
+  const [rotation, setRotation] = useState(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    refresh();
+  }, []);
+
+  const refresh = async () => {
+    try {
+      const next = await desktop.vaultStatus();
+      setStatus(next);
+      setNames(next.unlocked ? await desktop.vaultList() : []);
+    } catch (error) {
+      console.error('Failed to load vault status:', error);
+    }
+  };
+
+  // Runs a vault command, then reloads the status (the key may have been dropped)
+  const run = async (action, success) => {
+    setBusy(true);
+    try {
+      await action();
+      if (success) toast.success(success);
+      return true;
+    } catch (error) {
+      toast.error(String(error));
+      return false;
+    } finally {
+      setBusy(false);
+      refresh();
+    }
+  };
+
+  const handleUnlock = async (e) => {
+    e.preventDefault();
+    const created = !status.exists;
+    if (await run(() => desktop.vaultUnlock(passphrase), created ? 'Vault created' : null)) {
+      setPassphrase('');
+    }
+  };
+
+  const handleSet = async (e) => {
+    e.preventDefault();
+    const name = secret.name.trim();
+    if (await run(() => desktop.vaultSet(name, secret.value), `Saved ${name}`)) {
+      setSecret({ name: '', value: '' });
+    }
+  };
+
+  const handleRotate = async (e) => {
+    e.preventDefault();
+    if (rotation.newPassphrase !== rotation.confirm) {
+      toast.error('The new passphrases do not match');
+      return;
+    }
+    if (await run(() => desktop.vaultRotate(rotation.passphrase, rotation.newPassphrase), 'Passphrase changed')) {
+      setRotation(null);
+    }
+  };
+
+  if (!status) return null;
+
+  if (!status.unlocked) {
+    return (
+      <form onSubmit={handleUnlock} className="space-y-2">
+        <p className="text-xs text-muted-foreground">
+          {status.exists
+            ? 'Unlock the vault so Apply resolves ${VAR} from it. It stays unlocked until you lock it or quit the app.'
+            : 'Store API keys encrypted instead of in plaintext .env files. Choose a passphrase of at least 8 characters to create the vault.'}
+        </p>
+        <div className="flex gap-2">
+          <Input
+            type="password"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            placeholder="Passphrase"
+            autoComplete={status.exists ? 'current-password' : 'new-password'}
+          />
+          <Button type="submit" size="sm" variant="outline" disabled={busy || !passphrase}>
+            {busy ? <Loader2 className="w-4 h-4 mr-2 animate-spin" /> : <Unlock className="w-4 h-4 mr-2" />}
+            {status.exists ? 'Unlock' : 'Create'}
+          </Button>
+        </div>
+      </form>
+    );
+  }
+
+  return (
+    <div className="space-y-4">
+      <div className="space-y-2">
+        {names.length === 0 && (
+          <p className="text-xs text-muted-foreground">No secrets yet</p>
+        )}
+        {names.map((name) => (
+          <div key={name} className="flex items-center justify-between p-2 bg-muted/50 rounded-lg">
+            <code className="text-xs text-foreground">{name}</code>
+            <Button
+              size="sm"
+              variant="ghost"
+              className="h-7 w-7 p-0"
+              onClick={() => run(() => desktop.vaultRemove(name), `Removed ${name}`)}
+              disabled={busy}
+              title={`Remove ${name}`}
+            >
+              <Trash2 className="w-3 h-3" />
+            </Button>
+          </div>
+        ))}
+      </div>
+
+      <form onSubmit={handleSet} className="flex gap-2">
+        <Input
+          value={secret.name}
+          onChange={(e) => setSecret(prev => ({ ...prev, name: e.target.value }))}
+          placeholder="GITHUB_TOKEN"
+          className="font-mono text-sm"
+        />
+        <Input
+          type="password"
+          value={secret.value}
+          onChange={(e) => setSecret(prev => ({ ...prev, value: e.target.value }))}
+          placeholder="Value"
+          autoComplete="off"
+        />
+        <Button type="submit" size="sm" variant="outline" disabled={busy || !secret.name.trim() || !secret.value}>
+          <Plus className="w-4 h-4 mr-2" />
+          Save
+        </Button>
+      </form>
+
+      {rotation ? (
+        <form onSubmit={handleRotate} className="space-y-2">
+          <Input
+            type="password"
+            value={rotation.passphrase}
+            onChange={(e) => setRotation(prev => ({ ...prev, passphrase: e.target.value }))}
+            placeholder="Current passphrase"
+            autoComplete="current-password"
+          />
+          <Input
+            type="password"
+            value={rotation.newPassphrase}
+            onChange={(e) => setRotation(prev => ({ ...prev, newPassphrase: e.target.value }))}
+            placeholder="New passphrase"
+            autoComplete="new-password"
+          />
+          <Input
+            type="password"
+            value={rotation.confirm}
+            onChange={(e) => setRotation(prev => ({ ...prev, confirm: e.target.value }))}
+            placeholder="Repeat new passphrase"
+            autoComplete="new-password"
+          />
+          <div className="flex gap-2">
+            <Button type="submit" size="sm" variant="outline" disabled={busy || !rotation.passphrase || !rotation.newPassphrase}>
+              {busy ? <Loader2 className="w-4 h-4 mr-2 animate-spin" /> : <RefreshCw className="w-4 h-4 mr-2" />}
+              Change Passphrase
+            </Button>
+            <Button type="button" size="sm" variant="ghost" onClick={() => setRotation(null)}>
+              Cancel
+            </Button>
+          </div>
+        </form>
+      ) : (
+        <div className="flex gap-2">
+          <Button
+            size="sm"
+            variant="outline"
+            onClick={() => setRotation({ passphrase: '', newPassphrase: '', confirm: '' })}
+          >
+            <KeyRound className="w-4 h-4 mr-2" />
+            Change Passphrase...
+          </Button>
+          <Button size="sm" variant="outline" onClick={() => run(() => desktop.vaultLock())}>
+            <Lock className="w-4 h-4 mr-2" />
+            Lock
+          </Button>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -455,9 +455,9 @@ export const api = {
     return request('/version');
   },
 
-  // Changelog
-  async getChangelog() {
-    return request('/changelog');
+  // Changelog of this install, or of the release of `version`
+  async getChangelog(version) {
+    return request(version ? `/changelog?version=${encodeURIComponent(version)}` : '/changelog');
   },
 
   // Restart server
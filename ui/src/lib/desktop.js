@@ -0,0 +1,212 @@
+/**
+ * Bridge to the native desktop host (Tauri)
+ * Only available when the UI runs inside the desktop app
+ */
+
+export function isDesktop() {
+  return typeof window !== 'undefined' && !!window.__TAURI_INTERNALS__;
+}
+
+async function invoke(command, args = {}) {
+  if (!isDesktop()) {
+    throw new Error('Not running in the desktop app');
+  }
+  return window.__TAURI_INTERNALS__.invoke(command, args);
+}
+
+// Subscribe to a host event; resolves to an unsubscribe function
+// (same protocol as listen() in @tauri-apps/api/event)
+async function listen(event, callback) {
+  const handler = window.__TAURI_INTERNALS__.transformCallback(callback);
+  const eventId = await invoke('plugin:event|listen', {
+    event,
+    target: { kind: 'Any' },
+    handler,
+  });
+  return () => {
+    window.__TAURI_EVENT_PLUGIN_INTERNALS__?.unregisterListener(event, eventId);
+    invoke('plugin:event|unlisten', { event, eventId }).catch(() => {});
+  };
+}
+
+export const desktop = {
+  // Open the host and server log folder
+  async openLogs() {
+    return invoke('open_logs');
+  },
+
+  // MCP registry (~/.claude/registry.json)
+  async registryList() {
+    return invoke('registry_list');
+  },
+
+  async registryAdd(name, config) {
+    return invoke('registry_add', { name, config });
+  },
+
+  async registryRemove(name) {
+    return invoke('registry_remove', { name });
+  },
+
+  // Tools of a registry MCP, started by the host
+  async mcpServerTools(name) {
+    return invoke('mcp_server_tools', { name });
+  },
+
+  // Run `claude /init` in a project folder
+  async claudeInit(dir) {
+    return invoke('claude_init_project', { dir });
+  },
+
+  // Files in registered projects and tool folders; other paths are refused
+  async readFile(path) {
+    return invoke('file_read', { path });
+  },
+
+  async writeFile(path, content) {
+    return invoke('file_write', { path, content });
+  },
+
+  async deleteFile(path) {
+    return invoke('file_delete', { path });
+  },
+
+  async copyFile(source, target) {
+    return invoke('file_copy', { source, target });
+  },
+
+  async renameFile(source, target) {
+    return invoke('file_rename', { source, target });
+  },
+
+  async createDir(path) {
+    return invoke('file_create_dir', { path });
+  },
+
+  // Shell in a pseudo-terminal started by the host; resolves to the session id
+  async terminalOpen(cwd, cols, rows) {
+    return invoke('terminal_open', { cwd, cols, rows });
+  },
+
+  async terminalWrite(id, data) {
+    return invoke('terminal_write', { id, data });
+  },
+
+  async terminalResize(id, cols, rows) {
+    return invoke('terminal_resize', { id, cols, rows });
+  },
+
+  async terminalClose(id) {
+    return invoke('terminal_close', { id });
+  },
+
+  // Shell output of every session; callback gets { id, data }
+  async onTerminalOutput(callback) {
+    return listen('terminal-output', (event) => callback(event.payload));
+  },
+
+  // Callback gets { id, exitCode }
+  async onTerminalExit(callback) {
+    return listen('terminal-exit', (event) => callback(event.payload));
+  },
+
+  // Encrypted secrets vault; resolves ${VAR} at apply time while unlocked
+  async vaultStatus() {
+    return invoke('vault_status');
+  },
+
+  // Creates the vault on first use
+  async vaultUnlock(passphrase) {
+    return invoke('vault_unlock', { passphrase });
+  },
+
+  async vaultLock() {
+    return invoke('vault_lock');
+  },
+
+  // Names only; values never leave the host
+  async vaultList() {
+    return invoke('vault_list');
+  },
+
+  async vaultSet(name, value) {
+    return invoke('vault_set', { name, value });
+  },
+
+  async vaultRemove(name) {
+    return invoke('vault_remove', { name });
+  },
+
+  async vaultRotate(passphrase, newPassphrase) {
+    return invoke('vault_rotate', { passphrase, newPassphrase });
+  },
+
+  // Merged mcps.json hierarchy with the file behind each value
+  async effectiveConfig(dir) {
+    return invoke('effective_config', { dir });
+  },
+
+  // Generate .mcp.json; with dryRun, returns the would-be files and diffs
+  async applyConfig(dir, { dryRun = false, strict = false } = {}) {
+    return invoke('apply_config', { dir, dryRun, strict });
+  },
+
+  // Apply to several tools (defaults to enabledTools); one outcome per tool
+  async applyTools(dir, { tools, dryRun = false, strict = false } = {}) {
+    return invoke('apply_tools', { dir, tools, dryRun, strict });
+  },
+
+  // Watch the project's config folders; callback gets { dir, paths }
+  async watchProject(dir, callback) {
+    await invoke('watch_project', { dir });
+    return listen('config-changed', (event) => callback(event.payload));
+  },
+
+  // Project opened by launching the app again; callback gets { dir }
+  async onOpenProject(callback) {
+    return listen('open-project', (event) => callback(event.payload));
+  },
+
+  // App update progress; callback gets { status, version, ... }
+  async onUpdateStatus(callback) {
+    return listen('update-status', (event) => callback(event.payload));
+  },
+
+  async cancelUpdate() {
+    return invoke('cancel_update');
+  },
+
+  async checkForUpdates() {
+    return invoke('check_for_updates');
+  },
+
+  async updateSettings() {
+    return invoke('update_settings');
+  },
+
+  async setUpdateSettings(settings) {
+    return invoke('set_update_settings', { settings });
+  },
+
+  // Whether an update that fails to start is rolled back on this install
+  async rollbackSupported() {
+    return invoke('rollback_supported');
+  },
+
+  // Offline update from a picked latest.json; null if cancelled
+  async updateFromFile() {
+    return invoke('update_from_file');
+  },
+
+  // Release notes window
+  async releaseNotes() {
+    return invoke('release_notes');
+  },
+
+  async answerUpdate(choice) {
+    return invoke('answer_update', { choice });
+  },
+
+};
+
+export default desktop;
@@ -1,8 +1,19 @@
 import React from 'react'
 import ReactDOM from 'react-dom/client'
 import App from '@/App.jsx'
+import ReleaseNotes from '@/pages/ReleaseNotes.jsx'
+import { ThemeProvider } from '@/components/ThemeProvider'
 import '@/index.css'
 
+// The desktop updater opens the release notes in a window of their own
+const view = new URLSearchParams(window.location.search).get('view')
+
 ReactDOM.createRoot(document.getElementById('root')).render(
-  <App />
+  view === 'release-notes' ? (
+    <ThemeProvider>
+      <ReleaseNotes />
+    </ThemeProvider>
+  ) : (
+    <App />
+  )
 )
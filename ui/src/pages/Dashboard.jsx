@@ -8,12 +8,14 @@ import FileExplorer from "@/components/FileExplorer";
 import ProjectSwitcher from "@/components/ProjectSwitcher";
 import WelcomeModal from "@/components/WelcomeModal";
 import AddProjectDialog from "@/components/AddProjectDialog";
+import UpdateProgress from "@/components/UpdateProgress";
 import ThemeToggle from "@/components/ThemeToggle";
 import { Button } from "@/components/ui/button";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Separator } from "@/components/ui/separator";
 import { toast } from "sonner";
 import api from "@/lib/api";
+import desktop, { isDesktop } from "@/lib/desktop";
 import { cn } from "@/lib/utils";
 import {
   PreferencesView,
@@ -72,6 +74,13 @@ const setStoredState = (key, value) => {
   }
 };
 
+const TOOL_NAMES = {
+  claude: 'Claude Code',
+  gemini: 'Gemini CLI',
+  antigravity: 'Antigravity',
+  codex: 'Codex CLI',
+};
+
 export default function Dashboard() {
   const [currentView, setCurrentView] = useState(() => getStoredState('currentView', 'explorer'));
   const [loading, setLoading] = useState(true);
@@ -221,8 +230,56 @@ export default function Dashboard() {
     }
   };
 
-  // File change detection polling
+  // Project passed to a second launch of the desktop app
+  useEffect(() => {
+    if (!isDesktop()) return;
+
+    let unlisten = null;
+    let cancelled = false;
+    desktop.onOpenProject(async ({ dir }) => {
+      try {
+        await api.switchProject(dir);
+        window.location.reload();
+      } catch (error) {
+        toast.error('Failed to switch project: ' + error.message);
+      }
+    }).then((stop) => {
+      if (cancelled) stop();
+      else unlisten = stop;
+    }).catch(() => {});
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, []);
+
+  // File change detection: native watcher in the desktop app
+  useEffect(() => {
+    if (!isDesktop() || !project?.dir) return;
+
+    let unlisten = null;
+    let cancelled = false;
+    desktop.watchProject(project.dir, () => {
+      toast.info('Files changed externally, reloading...');
+      loadData();
+    }).then((stop) => {
+      if (cancelled) stop();
+      else unlisten = stop;
+    }).catch((error) => {
+      console.error('Failed to watch project:', error);
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [project?.dir, loadData]);
+
+  // File change detection polling (browser)
   useEffect(() => {
+    if (isDesktop()) return;
+
     const checkFileChanges = async () => {
       try {
         const { hashes } = await api.getFileHashes();
@@ -265,14 +322,29 @@ export default function Dashboard() {
     commands: commands.length,
   };
 
+  // The desktop host applies with the vault's secrets; the server only sees .env files
+  const applyEnabledTools = async (dir) => {
+    if (!isDesktop()) return api.applyConfig(dir);
+
+    const outcomes = await desktop.applyTools(dir);
+    const tools = {};
+    for (const [tool, outcome] of Object.entries(outcomes)) {
+      tools[tool] = outcome.status === 'applied';
+      if (outcome.status === 'failed') {
+        toast.error(`${TOOL_NAMES[tool] || tool}: ${outcome.error}`);
+      }
+    }
+    return { tools };
+  };
+
   const handleApplyConfig = async () => {
     try {
-      const result = await api.applyConfig(project.dir);
+      const result = await applyEnabledTools(project.dir);
       // Show which tools were updated
       if (result.tools) {
         const updated = Object.entries(result.tools)
           .filter(([, success]) => success)
-          .map(([tool]) => tool === 'claude' ? 'Claude Code' : 'Antigravity');
+          .map(([tool]) => TOOL_NAMES[tool] || tool);
         if (updated.length > 0) {
           toast.success(`Config applied to: ${updated.join(', ')}`);
         } else {
@@ -282,7 +354,7 @@ export default function Dashboard() {
         toast.success('Configuration applied successfully!');
       }
     } catch (error) {
-      toast.error('Failed to apply config: ' + error.message);
+      toast.error('Failed to apply config: ' + (error.message || error));
     }
   };
 
@@ -493,6 +565,9 @@ export default function Dashboard() {
 
       {/* First-time Welcome Modal */}
       <WelcomeModal onStartTutorial={() => setCurrentView('tutorial')} />
+
+      {/* Desktop app update progress */}
+      <UpdateProgress />
     </div>
   );
 }
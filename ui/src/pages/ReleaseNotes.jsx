@@ -0,0 +1,153 @@
+import React, { useEffect, useState } from 'react';
+import ReactMarkdown from 'react-markdown';
+import { Clock, Download, Loader2, LogOut, SkipForward } from 'lucide-react';
+import { Button } from '@/components/ui/button';
+import { ScrollArea } from '@/components/ui/scroll-area';
+import api from '@/lib/api';
+import desktop from '@/lib/desktop';
+
+const markdownComponents = {
+  h1: ({children}) => <h1 className="text-xl font-bold text-foreground mb-4">{children}</h1>,
+  h2: ({children}) => <h2 className="text-lg font-semibold text-foreground mt-6 mb-3 pb-2 border-b border-border">{children}</h2>,
+  h3: ({children}) => <h3 className="text-base font-medium text-foreground mt-4 mb-2">{children}</h3>,
+  p: ({children}) => <p className="text-sm text-muted-foreground mb-2">{children}</p>,
+  ul: ({children}) => <ul className="text-sm text-muted-foreground list-disc pl-5 mb-3 space-y-1">{children}</ul>,
+  li: ({children}) => <li className="text-sm">{children}</li>,
+  code: ({children}) => <code className="bg-muted px-1 py-0.5 rounded text-xs font-mono">{children}</code>,
+  hr: () => <hr className="my-4 border-border" />,
+  strong: ({children}) => <strong className="font-semibold text-foreground">{children}</strong>,
+};
+
+function compareVersions(a, b) {
+  const pa = a.split('-')[0].split('.').map(Number);
+  const pb = b.split('-')[0].split('.').map(Number);
+  for (let i = 0; i < 3; i++) {
+    const diff = (pa[i] || 0) - (pb[i] || 0);
+    if (diff !== 0) return diff;
+  }
+  return 0;
+}
+
+/**
+ * Keep the `## [x.y.z]` sections of CHANGELOG.md newer than `from`, up to `to`
+ */
+function changelogBetween(content, from, to) {
+  const sections = content.split(/^(?=## \[)/m);
+  return sections
+    .filter((section) => {
+      const match = section.match(/^## \[([^\]]+)\]/);
+      if (!match) return false;
+      const version = match[1];
+      return compareVersions(version, from) > 0 && compareVersions(version, to) <= 0;
+    })
+    .join('\n');
+}
+
+/**
+ * Release notes window opened by the desktop updater
+ */
+export default function ReleaseNotes() {
+  const [notes, setNotes] = useState(null);
+  const [changelog, setChangelog] = useState('');
+  const [loading, setLoading] = useState(true);
+  const [answering, setAnswering] = useState(false);
+
+  useEffect(() => {
+    const load = async () => {
+      try {
+        const data = await desktop.releaseNotes();
+        setNotes(data);
+        if (data) {
+          // The installed changelog ends at the installed version; the
+          // offered release's own changelog has the entries in between
+          const result = await api.getChangelog(data.version).catch(() => null);
+          if (result?.success) {
+            setChangelog(changelogBetween(result.content, data.currentVersion, data.version));
+          }
+        }
+      } catch (error) {
+        console.error('Failed to load release notes:', error);
+      } finally {
+        setLoading(false);
+      }
+    };
+    load();
+  }, []);
+
+  const answer = async (choice) => {
+    setAnswering(true);
+    try {
+      await desktop.answerUpdate(choice);
+    } catch (error) {
+      console.error('Failed to answer update:', error);
+      setAnswering(false);
+    }
+  };
+
+  if (loading) {
+    return (
+      <div className="h-screen flex items-center justify-center">
+        <Loader2 className="w-6 h-6 animate-spin text-muted-foreground" />
+      </div>
+    );
+  }
+
+  if (!notes) {
+    return (
+      <div className="h-screen flex items-center justify-center text-sm text-muted-foreground">
+        No update is being offered.
+      </div>
+    );
+  }
+
+  return (
+    <div className="h-screen flex flex-col bg-background">
+      <div className="px-6 pt-6 pb-4 border-b border-border">
+        <h1 className="text-lg font-semibold text-foreground">Version {notes.version} is available</h1>
+        <p className="text-sm text-muted-foreground">
+          You have version {notes.currentVersion}
+        </p>
+      </div>
+
+      <ScrollArea className="flex-1">
+        <div className="prose prose-sm dark:prose-invert max-w-none px-6 py-4">
+          {notes.body && (
+            <ReactMarkdown components={markdownComponents}>{notes.body}</ReactMarkdown>
+          )}
+          {changelog && (
+            <>
+              <h2 className="text-lg font-semibold text-foreground mt-6 mb-3">Changes since {notes.currentVersion}</h2>
+              <ReactMarkdown components={markdownComponents}>{changelog}</ReactMarkdown>
+            </>
+          )}
+          {!notes.body && !changelog && (
+            <p className="text-sm text-muted-foreground">No release notes were published for this version.</p>
+          )}
+        </div>
+      </ScrollArea>
+
+      <div className="px-6 py-4 border-t border-border flex items-center justify-between gap-2">
+        <div className="flex gap-2">
+          <Button variant="ghost" size="sm" disabled={answering} onClick={() => answer('skip')}>
+            <SkipForward className="w-4 h-4 mr-2" />
+            Skip This Version
+          </Button>
+          <Button variant="ghost" size="sm" disabled={answering} onClick={() => answer('later')}>
+            <Clock className="w-4 h-4 mr-2" />
+            Remind Me Later
+          </Button>
+        </div>
+        <div className="flex gap-2">
+          <Button variant="outline" size="sm" disabled={answering} onClick={() => answer('onQuit')}>
+            <LogOut className="w-4 h-4 mr-2" />
+            Install on Quit
+          </Button>
+          <Button size="sm" disabled={answering} onClick={() => answer('now')}>
+            <Download className="w-4 h-4 mr-2" />
+            Update Now
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,9 +1,16 @@
 import React, { useState, useEffect } from 'react';
 import ReactMarkdown from 'react-markdown';
-import { Wrench, Folder, Layout, FileText, Save, Loader2, RefreshCw, Download, FolderOpen, Plus, X, FolderPlus, Cpu, Sparkles, Bot, Terminal, Eye, EyeOff, History } from 'lucide-react';
+import { Wrench, Folder, Layout, FileText, Save, Loader2, RefreshCw, Download, FolderOpen, Plus, X, FolderPlus, Cpu, Sparkles, Bot, Terminal, Eye, EyeOff, History, KeyRound } from 'lucide-react';
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Switch } from "@/components/ui/switch";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import {
   Dialog,
   DialogContent,
@@ -13,7 +20,9 @@ import {
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { toast } from "sonner";
 import api from "@/lib/api";
+import desktop, { isDesktop } from "@/lib/desktop";
 import PathPicker from "@/components/PathPicker";
+import VaultSettings from "@/components/VaultSettings";
 
 export default function PreferencesView() {
   const [config, setConfig] = useState(null);
@@ -26,13 +35,51 @@ export default function PreferencesView() {
   const [projectDir, setProjectDir] = useState(null);
   const [hiddenSubprojects, setHiddenSubprojects] = useState([]);
   const [changelogDialog, setChangelogDialog] = useState({ open: false, content: '', loading: false });
+  const [updateSettings, setUpdateSettings] = useState(null);
+  const [rollbackSupported, setRollbackSupported] = useState(true);
+  const [checkingUpdates, setCheckingUpdates] = useState(false);
 
   useEffect(() => {
     loadConfig();
     loadVersionInfo();
     loadProjectInfo();
+    loadUpdateSettings();
   }, []);
 
+  const loadUpdateSettings = async () => {
+    if (!isDesktop()) return;
+    try {
+      setUpdateSettings(await desktop.updateSettings());
+      setRollbackSupported(await desktop.rollbackSupported());
+    } catch (error) {
+      console.error('Failed to load update settings:', error);
+    }
+  };
+
+  const changeUpdateSettings = async (changes) => {
+    const settings = { ...updateSettings, ...changes };
+    try {
+      await desktop.setUpdateSettings(settings);
+      setUpdateSettings(settings);
+    } catch (error) {
+      toast.error('Failed to save update settings: ' + error);
+    }
+  };
+
+  const handleCheckForUpdates = async (fromFile = false) => {
+    setCheckingUpdates(true);
+    try {
+      const result = fromFile ? await desktop.updateFromFile() : await desktop.checkForUpdates();
+      if (result && !result.version) {
+        toast.success(`You're up to date (v${result.currentVersion})`);
+      }
+    } catch (error) {
+      toast.error('Failed to check for updates: ' + error);
+    } finally {
+      setCheckingUpdates(false);
+    }
+  };
+
   const loadVersionInfo = async () => {
     try {
       const data = await api.checkVersion();
@@ -89,7 +136,9 @@ export default function PreferencesView() {
         sourcePath: versionInfo.sourcePath
       });
       if (result.success) {
-        if (result.updateMethod === 'npm') {
+        if (result.updateMethod === 'desktop') {
+          toast.success(result.message);
+        } else if (result.updateMethod === 'npm') {
           toast.success(result.message || 'Updated via npm! Restart the server to apply.');
         } else {
           toast.success(`Updated! Files: ${result.updated?.join(', ') || 'all'}. Restart the server to apply.`);
@@ -121,6 +170,14 @@ export default function PreferencesView() {
     }
   };
 
+  const handleOpenLogs = async () => {
+    try {
+      await desktop.openLogs();
+    } catch (error) {
+      toast.error('Failed to open logs: ' + error);
+    }
+  };
+
   const loadConfig = async () => {
     try {
       setLoading(true);
@@ -503,6 +560,21 @@ export default function PreferencesView() {
                 Note: Antigravity does not support environment variable interpolation (<code>$&#123;VAR&#125;</code>).
                 Variables are resolved to actual values when generating its config.
               </p>
+
+              {isDesktop() && (
+                <div className="flex items-center justify-between">
+                  <div>
+                    <label className="text-sm font-medium text-foreground">Apply Automatically</label>
+                    <p className="text-xs text-muted-foreground">
+                      Regenerate tool configs when mcps.json, .env or the registry changes
+                    </p>
+                  </div>
+                  <Switch
+                    checked={config?.autoApply ?? false}
+                    onCheckedChange={(checked) => updateConfig('autoApply', checked)}
+                  />
+                </div>
+              )}
             </div>
           </div>
 
@@ -544,6 +616,131 @@ export default function PreferencesView() {
             </div>
           )}
 
+          {/* Desktop Secrets Vault Section */}
+          {isDesktop() && (
+            <div>
+              <h3 className="text-sm font-medium text-foreground mb-4 flex items-center gap-2">
+                <KeyRound className="w-4 h-4" />
+                Secrets Vault
+              </h3>
+              <VaultSettings />
+            </div>
+          )}
+
+          {/* Desktop Updates Section */}
+          {isDesktop() && updateSettings && (
+            <div>
+              <h3 className="text-sm font-medium text-foreground mb-4 flex items-center gap-2">
+                <Download className="w-4 h-4" />
+                Updates
+              </h3>
+
+              <div className="space-y-4">
+                <div className="flex items-center justify-between">
+                  <div>
+                    <label className="text-sm font-medium text-foreground">Channel</label>
+                    <p className="text-xs text-muted-foreground">
+                      Beta releases arrive earlier but may be less stable
+                    </p>
+                  </div>
+                  <Select
+                    value={updateSettings.channel}
+                    onValueChange={(channel) => changeUpdateSettings({ channel })}
+                  >
+                    <SelectTrigger className="w-32">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="stable">Stable</SelectItem>
+                      <SelectItem value="beta">Beta</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+
+                <div className="flex items-center justify-between">
+                  <div>
+                    <label className="text-sm font-medium text-foreground">Check Every</label>
+                    <p className="text-xs text-muted-foreground">
+                      How often to look for updates while the app is open
+                    </p>
+                  </div>
+                  <Select
+                    value={String(updateSettings.checkIntervalHours)}
+                    onValueChange={(hours) => changeUpdateSettings({ checkIntervalHours: Number(hours) })}
+                  >
+                    <SelectTrigger className="w-32">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      <SelectItem value="1">Hour</SelectItem>
+                      <SelectItem value="6">6 hours</SelectItem>
+                      <SelectItem value="24">Day</SelectItem>
+                      <SelectItem value="0">Startup only</SelectItem>
+                    </SelectContent>
+                  </Select>
+                </div>
+
+                <div>
+                  <label className="text-sm font-medium text-foreground">Local Source</label>
+                  <p className="text-xs text-muted-foreground mb-2">
+                    Folder, latest.json or file:// URL to update from instead of GitHub, for machines without internet (offline builds only)
+                  </p>
+                  <Input
+                    value={updateSettings.localSource || ''}
+                    onChange={(e) => setUpdateSettings(prev => ({ ...prev, localSource: e.target.value }))}
+                    onBlur={(e) => changeUpdateSettings({ localSource: e.target.value.trim() || null })}
+                    placeholder="/Volumes/updates"
+                    className="font-mono text-sm"
+                  />
+                </div>
+
+                {updateSettings.skippedVersion && (
+                  <div className="flex items-center justify-between p-3 bg-muted/50 rounded-lg">
+                    <p className="text-sm text-foreground">
+                      Skipping version <code className="text-xs">{updateSettings.skippedVersion}</code>
+                    </p>
+                    <Button
+                      size="sm"
+                      variant="outline"
+                      onClick={() => changeUpdateSettings({ skippedVersion: null })}
+                      className="h-7"
+                    >
+                      <X className="w-3 h-3 mr-1" />
+                      Clear
+                    </Button>
+                  </div>
+                )}
+
+                <div className="flex gap-2">
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => handleCheckForUpdates()}
+                    disabled={checkingUpdates}
+                  >
+                    {checkingUpdates ? <Loader2 className="w-4 h-4 mr-2 animate-spin" /> : <RefreshCw className="w-4 h-4 mr-2" />}
+                    Check for Updates
+                  </Button>
+                  <Button
+                    size="sm"
+                    variant="outline"
+                    onClick={() => handleCheckForUpdates(true)}
+                    disabled={checkingUpdates}
+                  >
+                    <FolderOpen className="w-4 h-4 mr-2" />
+                    Install from File...
+                  </Button>
+                </div>
+
+                <p className="text-xs text-muted-foreground">
+                  {rollbackSupported
+                    ? 'An update that fails to start three times is rolled back to the previous version'
+                    : 'Updates that fail to start are not rolled back on this install (only on macOS and with the Linux AppImage); reinstall the previous version from GitHub if needed'}
+                </p>
+              </div>
+            </div>
+          )}
+
           {/* About Section */}
           <div>
             <h3 className="text-sm font-medium text-foreground mb-4 flex items-center gap-2">
@@ -563,7 +760,7 @@ export default function PreferencesView() {
                     <div>
                       <p className="text-sm font-medium text-green-600 dark:text-green-400">Update Available!</p>
                       <p className="text-xs text-green-600/80 dark:text-green-400/80">
-                        Version {versionInfo.latestVersion} via {versionInfo.updateMethod === 'npm' ? 'npm' : versionInfo.sourcePath}
+                        Version {versionInfo.latestVersion} via {versionInfo.updateMethod === 'desktop' ? 'the desktop app' : versionInfo.updateMethod === 'npm' ? 'npm' : versionInfo.sourcePath}
                       </p>
                     </div>
                     <Button
@@ -631,9 +828,23 @@ export default function PreferencesView() {
                     Changelog
                   </button>
                 </div>
-                <p className="text-xs text-muted-foreground mt-2">
-                  After updating, restart the server with <code className="bg-muted-foreground/10 px-1 rounded">claude-config ui</code>
-                </p>
+                {isDesktop() && (
+                  <div className="flex justify-between text-sm">
+                    <span className="text-muted-foreground">Logs</span>
+                    <button
+                      onClick={handleOpenLogs}
+                      className="text-primary hover:underline flex items-center gap-1"
+                    >
+                      <FolderOpen className="w-3 h-3" />
+                      Open logs
+                    </button>
+                  </div>
+                )}
+                {!isDesktop() && (
+                  <p className="text-xs text-muted-foreground mt-2">
+                    After updating, restart the server with <code className="bg-muted-foreground/10 px-1 rounded">claude-config ui</code>
+                  </p>
+                )}
               </div>
             </div>
           </div>
@@ -5,6 +5,7 @@
 
 const WebSocket = require('ws');
 const os = require('os');
+const desktopAuth = require('./routes/desktop-auth');
 let pty;
 
 try {
@@ -31,7 +32,8 @@ class TerminalServer {
 
     this.wss = new WebSocket.Server({
       server: httpServer,
-      path: '/ws/terminal'
+      path: '/ws/terminal',
+      verifyClient: ({ req }) => desktopAuth.isAuthorized(req)
     });
 
     this.wss.on('connection', (ws, req) => {